@@ -1,9 +1,20 @@
 //! Analog-to-digital converters.
 
-use crate::{
-    common::{DrvClockSel, DrvDmaRx, DrvRcc},
-    dma::DmaChEn,
-};
+#[cfg(any(
+    stm32_mcu = "stm32l4x1",
+    stm32_mcu = "stm32l4x2",
+    stm32_mcu = "stm32l4x3",
+    stm32_mcu = "stm32l4x5",
+    stm32_mcu = "stm32l4x6",
+    stm32_mcu = "stm32l4r5",
+    stm32_mcu = "stm32l4r7",
+    stm32_mcu = "stm32l4r9",
+    stm32_mcu = "stm32l4s5",
+    stm32_mcu = "stm32l4s7",
+    stm32_mcu = "stm32l4s9"
+))]
+use crate::common::DmaRxReq;
+use crate::common::{DrvClockSel, DrvDmaRx, DrvRcc};
 #[cfg(any(
     stm32_mcu = "stm32l4s5",
     stm32_mcu = "stm32l4s7",
@@ -12,12 +23,19 @@ use crate::{
 use core::ptr::read_volatile;
 use drone_core::inventory::{self, Inventory0, Inventory1};
 use drone_cortex_m::{fib, reg::prelude::*, thr::prelude::*};
-use drone_stm32_map::periph::{
-    adc::{traits::*, AdcMap, AdcPeriph},
-    dma::ch::DmaChMap,
-};
+use drone_stm32_map::periph::adc::{traits::*, AdcMap, AdcPeriph};
 use futures::prelude::*;
 
+dma_ch! {
+    use crate::dma::DmaChEn;
+    use drone_stm32_map::periph::dma::ch::DmaChMap;
+}
+
+dma_st! {
+    use crate::dma::DmaStEn;
+    use drone_stm32_map::periph::dma::st::DmaStMap;
+}
+
 #[cfg(any(
     stm32_mcu = "stm32l4r5",
     stm32_mcu = "stm32l4r7",
@@ -231,17 +249,78 @@ impl<T: AdcMap, I: IntToken> inventory::Item for AdcEn<T, I> {
     }
 }
 
-impl<T: AdcMap, I: IntToken, Rx: DmaChMap> DrvDmaRx<Rx> for Adc<T, I> {
-    #[inline]
-    fn dma_rx_paddr_init(&self, dma_rx: &DmaChEn<Rx, impl IntToken>) {
-        self.0.dma_rx_paddr_init(dma_rx);
+dma_ch! {
+    impl<T: AdcMap, I: IntToken, Rx: DmaChMap> DrvDmaRx<Rx> for Adc<T, I> {
+        #[inline]
+        fn dma_rx_paddr_init(&self, dma_rx: &DmaChEn<Rx, impl IntToken>) {
+            self.0.dma_rx_paddr_init(dma_rx);
+        }
+    }
+
+    impl<T: AdcMap, I: IntToken, Rx: DmaChMap> DrvDmaRx<Rx> for AdcEn<T, I> {
+        fn dma_rx_paddr_init(&self, dma_rx: &DmaChEn<Rx, impl IntToken>) {
+            unsafe { dma_rx.set_paddr(self.periph.adc_dr.as_ptr()) };
+        }
     }
 }
 
-impl<T: AdcMap, I: IntToken, Rx: DmaChMap> DrvDmaRx<Rx> for AdcEn<T, I> {
-    fn dma_rx_paddr_init(&self, dma_rx: &DmaChEn<Rx, impl IntToken>) {
-        unsafe { dma_rx.set_paddr(self.periph.adc_dr.as_ptr()) };
+dma_st! {
+    impl<T: AdcMap, I: IntToken, Rx: DmaStMap> DrvDmaRx<Rx> for Adc<T, I> {
+        #[inline]
+        fn dma_rx_paddr_init(&self, dma_rx: &DmaStEn<Rx, impl IntToken>) {
+            self.0.dma_rx_paddr_init(dma_rx);
+        }
     }
+
+    impl<T: AdcMap, I: IntToken, Rx: DmaStMap> DrvDmaRx<Rx> for AdcEn<T, I> {
+        fn dma_rx_paddr_init(&self, dma_rx: &DmaStEn<Rx, impl IntToken>) {
+            unsafe { dma_rx.set_paddr(self.periph.adc_dr.as_ptr()) };
+        }
+    }
+}
+
+#[cfg(any(
+    stm32_mcu = "stm32l4x1",
+    stm32_mcu = "stm32l4x2",
+    stm32_mcu = "stm32l4x3",
+    stm32_mcu = "stm32l4x5",
+    stm32_mcu = "stm32l4x6",
+    stm32_mcu = "stm32l4r5",
+    stm32_mcu = "stm32l4r7",
+    stm32_mcu = "stm32l4r9",
+    stm32_mcu = "stm32l4s5",
+    stm32_mcu = "stm32l4s7",
+    stm32_mcu = "stm32l4s9"
+))]
+impl<T, I, Rx> DmaRxReq<Rx> for Adc<T, I>
+where
+    T: AdcMap + DmaRxReq<Rx>,
+    I: IntToken,
+    Rx: DmaChMap,
+{
+    const DMA_RX_REQ: u32 = T::DMA_RX_REQ;
+}
+
+#[cfg(any(
+    stm32_mcu = "stm32l4x1",
+    stm32_mcu = "stm32l4x2",
+    stm32_mcu = "stm32l4x3",
+    stm32_mcu = "stm32l4x5",
+    stm32_mcu = "stm32l4x6",
+    stm32_mcu = "stm32l4r5",
+    stm32_mcu = "stm32l4r7",
+    stm32_mcu = "stm32l4r9",
+    stm32_mcu = "stm32l4s5",
+    stm32_mcu = "stm32l4s7",
+    stm32_mcu = "stm32l4s9"
+))]
+impl<T, I, Rx> DmaRxReq<Rx> for AdcEn<T, I>
+where
+    T: AdcMap + DmaRxReq<Rx>,
+    I: IntToken,
+    Rx: DmaChMap,
+{
+    const DMA_RX_REQ: u32 = T::DMA_RX_REQ;
 }
 
 impl<T: AdcMap, I: IntToken> DrvRcc for Adc<T, I> {
@@ -295,3 +374,68 @@ impl<T: AdcMap, I: IntToken> DrvClockSel for AdcEn<T, I> {
 pub fn read_vref_cal() -> u16 {
     unsafe { read_volatile(0x1FFF_75AA as *const u16) }
 }
+
+#[cfg(any(
+    stm32_mcu = "stm32l4x1",
+    stm32_mcu = "stm32l4x2",
+    stm32_mcu = "stm32l4x3",
+    stm32_mcu = "stm32l4x5",
+    stm32_mcu = "stm32l4x6"
+))]
+mod dma_req {
+    use crate::common::DmaRxReq;
+    use drone_stm32_map::periph::{
+        adc::Adc1,
+        dma::ch::{Dma1Ch1, Dma2Ch3},
+    };
+    #[cfg(any(stm32_mcu = "stm32l4x5", stm32_mcu = "stm32l4x6"))]
+    use drone_stm32_map::periph::{
+        adc::{Adc2, Adc3},
+        dma::ch::{Dma1Ch2, Dma1Ch3, Dma2Ch4, Dma2Ch5},
+    };
+
+    impl DmaRxReq<Dma1Ch1> for Adc1 {
+        const DMA_RX_REQ: u32 = 0;
+    }
+
+    impl DmaRxReq<Dma2Ch3> for Adc1 {
+        const DMA_RX_REQ: u32 = 0;
+    }
+
+    #[cfg(any(stm32_mcu = "stm32l4x5", stm32_mcu = "stm32l4x6"))]
+    impl DmaRxReq<Dma1Ch2> for Adc2 {
+        const DMA_RX_REQ: u32 = 0;
+    }
+
+    #[cfg(any(stm32_mcu = "stm32l4x5", stm32_mcu = "stm32l4x6"))]
+    impl DmaRxReq<Dma2Ch4> for Adc2 {
+        const DMA_RX_REQ: u32 = 0;
+    }
+
+    #[cfg(any(stm32_mcu = "stm32l4x5", stm32_mcu = "stm32l4x6"))]
+    impl DmaRxReq<Dma1Ch3> for Adc3 {
+        const DMA_RX_REQ: u32 = 0;
+    }
+
+    #[cfg(any(stm32_mcu = "stm32l4x5", stm32_mcu = "stm32l4x6"))]
+    impl DmaRxReq<Dma2Ch5> for Adc3 {
+        const DMA_RX_REQ: u32 = 0;
+    }
+}
+
+#[cfg(any(
+    stm32_mcu = "stm32l4r5",
+    stm32_mcu = "stm32l4r7",
+    stm32_mcu = "stm32l4r9",
+    stm32_mcu = "stm32l4s5",
+    stm32_mcu = "stm32l4s7",
+    stm32_mcu = "stm32l4s9"
+))]
+mod dma_req {
+    use crate::common::DmaRxReq;
+    use drone_stm32_map::periph::{adc::Adc1, dma::ch::DmaChMap};
+
+    impl<Rx: DmaChMap> DmaRxReq<Rx> for Adc1 {
+        const DMA_RX_REQ: u32 = 5;
+    }
+}
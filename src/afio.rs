@@ -0,0 +1,358 @@
+//! Alternate function I/O of STM32F1.
+//!
+//! EXTI lines are routed to GPIO ports with
+//! [`Exti::set_port`](crate::exti::Exti::set_port), which requires the AFIO
+//! clock enabled by this driver.
+
+use drone_core::inventory::{self, Inventory0, Inventory1};
+use drone_cortex_m::reg::prelude::*;
+use drone_stm32_map::periph::afio::AfioPeriph;
+
+/// USART3 pins remapping.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Usart3Remap {
+    /// TX/PB10, RX/PB11, CK/PB12, CTS/PB13, RTS/PB14.
+    None,
+    /// TX/PC10, RX/PC11, CK/PC12, CTS/PB13, RTS/PB14.
+    Partial,
+    /// TX/PD8, RX/PD9, CK/PD10, CTS/PD11, RTS/PD12.
+    Full,
+}
+
+/// TIM1 pins remapping.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tim1Remap {
+    /// ETR/PA12, CH1/PA8, CH2/PA9, CH3/PA10, CH4/PA11, BKIN/PB12,
+    /// CH1N/PB13, CH2N/PB14, CH3N/PB15.
+    None,
+    /// ETR/PA12, CH1/PA8, CH2/PA9, CH3/PA10, CH4/PA11, BKIN/PA6, CH1N/PA7,
+    /// CH2N/PB0, CH3N/PB1.
+    Partial,
+    /// ETR/PE7, CH1/PE9, CH2/PE11, CH3/PE13, CH4/PE14, BKIN/PE15,
+    /// CH1N/PE8, CH2N/PE10, CH3N/PE12.
+    Full,
+}
+
+/// TIM2 pins remapping.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tim2Remap {
+    /// CH1/ETR/PA0, CH2/PA1, CH3/PA2, CH4/PA3.
+    None,
+    /// CH1/ETR/PA15, CH2/PB3, CH3/PA2, CH4/PA3.
+    Partial1,
+    /// CH1/ETR/PA0, CH2/PA1, CH3/PB10, CH4/PB11.
+    Partial2,
+    /// CH1/ETR/PA15, CH2/PB3, CH3/PB10, CH4/PB11.
+    Full,
+}
+
+/// TIM3 pins remapping.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tim3Remap {
+    /// CH1/PA6, CH2/PA7, CH3/PB0, CH4/PB1.
+    None,
+    /// CH1/PB4, CH2/PB5, CH3/PB0, CH4/PB1.
+    Partial,
+    /// CH1/PC6, CH2/PC7, CH3/PC8, CH4/PC9.
+    Full,
+}
+
+/// Serial wire JTAG configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SwjCfg {
+    /// Full SWJ (JTAG-DP + SW-DP). Reset state.
+    Full,
+    /// Full SWJ (JTAG-DP + SW-DP) but without NJTRST. Releases PB4.
+    NoNjtrst,
+    /// JTAG-DP disabled and SW-DP enabled. Releases PA15, PB3, and PB4.
+    SwdOnly,
+    /// JTAG-DP disabled and SW-DP disabled. Releases PA13, PA14, PA15, PB3,
+    /// and PB4.
+    Disabled,
+}
+
+/// Pins remapping configuration.
+///
+/// `AFIO_MAPR_SWJ_CFG` field is write-only, therefore the whole
+/// `AFIO_MAPR` register is written at once from this value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AfioRemap {
+    /// SPI1: NSS/PA15, SCK/PB3, MISO/PB4, MOSI/PB5 if set; NSS/PA4,
+    /// SCK/PA5, MISO/PA6, MOSI/PA7 otherwise.
+    pub spi1: bool,
+    /// I2C1: SCL/PB8, SDA/PB9 if set; SCL/PB6, SDA/PB7 otherwise.
+    pub i2c1: bool,
+    /// USART1: TX/PB6, RX/PB7 if set; TX/PA9, RX/PA10 otherwise.
+    pub usart1: bool,
+    /// USART2: CTS/PD3, RTS/PD4, TX/PD5, RX/PD6, CK/PD7 if set; CTS/PA0,
+    /// RTS/PA1, TX/PA2, RX/PA3, CK/PA4 otherwise.
+    pub usart2: bool,
+    /// USART3 remapping.
+    pub usart3: Usart3Remap,
+    /// TIM1 remapping.
+    pub tim1: Tim1Remap,
+    /// TIM2 remapping.
+    pub tim2: Tim2Remap,
+    /// TIM3 remapping.
+    pub tim3: Tim3Remap,
+    /// TIM4: CH1/PD12, CH2/PD13, CH3/PD14, CH4/PD15 if set; CH1/PB6,
+    /// CH2/PB7, CH3/PB8, CH4/PB9 otherwise.
+    pub tim4: bool,
+    /// OSC_IN/OSC_OUT mapped on PD0/PD1.
+    pub pd01: bool,
+    /// Serial wire JTAG configuration.
+    pub swj: SwjCfg,
+}
+
+macro_rules! remap_token {
+    ($($(#[$attr:meta])* $name:ident,)*) => {
+        $(
+            $(#[$attr])*
+            #[derive(Debug)]
+            pub struct $name(());
+        )*
+    };
+}
+
+remap_token! {
+    /// Proof of SPI1 pins being not remapped.
+    Spi1NotRemapped,
+    /// Proof of SPI1 pins being remapped.
+    Spi1Remapped,
+    /// Proof of USART1 pins being not remapped.
+    Usart1NotRemapped,
+    /// Proof of USART1 pins being remapped.
+    Usart1Remapped,
+    /// Proof of USART2 pins being not remapped.
+    Usart2NotRemapped,
+    /// Proof of USART2 pins being remapped.
+    Usart2Remapped,
+    /// Proof of USART3 pins being not remapped.
+    Usart3NotRemapped,
+    /// Proof of USART3 pins being partially remapped.
+    Usart3PartialRemapped,
+    /// Proof of USART3 pins being fully remapped.
+    Usart3FullRemapped,
+}
+
+/// Proofs of the pins mapping returned by
+/// [`AfioEn::set_remap`](AfioEn::set_remap).
+///
+/// The `setup_pins` methods of the drivers consume the corresponding proof,
+/// both for the default and for the remapped pins. Exactly one proof is
+/// present for each peripheral.
+#[derive(Debug)]
+pub struct AfioRemapped {
+    /// Present if SPI1 is not remapped.
+    pub spi1_not_remapped: Option<Spi1NotRemapped>,
+    /// Present if SPI1 is remapped.
+    pub spi1: Option<Spi1Remapped>,
+    /// Present if USART1 is not remapped.
+    pub usart1_not_remapped: Option<Usart1NotRemapped>,
+    /// Present if USART1 is remapped.
+    pub usart1: Option<Usart1Remapped>,
+    /// Present if USART2 is not remapped.
+    pub usart2_not_remapped: Option<Usart2NotRemapped>,
+    /// Present if USART2 is remapped.
+    pub usart2: Option<Usart2Remapped>,
+    /// Present if USART3 is not remapped.
+    pub usart3_not_remapped: Option<Usart3NotRemapped>,
+    /// Present if USART3 is partially remapped.
+    pub usart3_partial: Option<Usart3PartialRemapped>,
+    /// Present if USART3 is fully remapped.
+    pub usart3_full: Option<Usart3FullRemapped>,
+}
+
+/// AFIO driver.
+pub struct Afio(Inventory0<AfioEn>);
+
+/// AFIO enabled driver.
+pub struct AfioEn {
+    periph: AfioPeriph,
+    remapped: bool,
+}
+
+impl Afio {
+    /// Creates a new [`Afio`].
+    #[inline]
+    pub fn new(periph: AfioPeriph) -> Self {
+        Self(Inventory0::new(AfioEn {
+            periph,
+            remapped: false,
+        }))
+    }
+
+    /// Releases the peripheral.
+    #[inline]
+    pub fn free(self) -> AfioPeriph {
+        Inventory0::free(self.0).periph
+    }
+
+    /// Enables AFIO clock.
+    pub fn enable(&mut self) -> inventory::Guard<'_, AfioEn> {
+        self.setup();
+        Inventory0::guard(&mut self.0)
+    }
+
+    /// Enables AFIO clock.
+    pub fn into_enabled(self) -> Inventory1<AfioEn> {
+        self.setup();
+        let (enabled, token) = self.0.share1();
+        // To be recreated in `from_enabled()`.
+        drop(token);
+        enabled
+    }
+
+    /// Disables AFIO clock.
+    pub fn from_enabled(enabled: Inventory1<AfioEn>) -> Self {
+        // Restoring the token dropped in `into_enabled()`.
+        let token = unsafe { inventory::Token::new() };
+        let mut enabled = enabled.merge1(token);
+        Inventory0::teardown(&mut enabled);
+        Self(enabled)
+    }
+
+    fn setup(&self) {
+        let afioen = &self.0.periph.rcc_apb2enr_afioen;
+        if afioen.read_bit() {
+            panic!("AFIO wasn't turned off");
+        }
+        afioen.set_bit();
+    }
+}
+
+impl AfioEn {
+    /// Applies the pins remapping configuration.
+    ///
+    /// Returns the proofs of the pins mapping, which are required to set up
+    /// the pins. The configuration can be changed again only after all the
+    /// proofs are given back with [`AfioEn::release_remap`].
+    ///
+    /// # Panics
+    ///
+    /// If the proofs of the previous call weren't given back.
+    pub fn set_remap(&mut self, remap: &AfioRemap) -> AfioRemapped {
+        if self.remapped {
+            panic!("AFIO remap proofs weren't released");
+        }
+        self.remapped = true;
+        let mapr = &self.periph.afio_mapr;
+        mapr.store_val({
+            let mut val = mapr.default_val();
+            if remap.spi1 {
+                mapr.spi1_remap().set(&mut val);
+            }
+            if remap.i2c1 {
+                mapr.i2c1_remap().set(&mut val);
+            }
+            if remap.usart1 {
+                mapr.usart1_remap().set(&mut val);
+            }
+            if remap.usart2 {
+                mapr.usart2_remap().set(&mut val);
+            }
+            mapr.usart3_remap().write(&mut val, match remap.usart3 {
+                Usart3Remap::None => 0b00,
+                Usart3Remap::Partial => 0b01,
+                Usart3Remap::Full => 0b11,
+            });
+            mapr.tim1_remap().write(&mut val, match remap.tim1 {
+                Tim1Remap::None => 0b00,
+                Tim1Remap::Partial => 0b01,
+                Tim1Remap::Full => 0b11,
+            });
+            mapr.tim2_remap().write(&mut val, match remap.tim2 {
+                Tim2Remap::None => 0b00,
+                Tim2Remap::Partial1 => 0b01,
+                Tim2Remap::Partial2 => 0b10,
+                Tim2Remap::Full => 0b11,
+            });
+            mapr.tim3_remap().write(&mut val, match remap.tim3 {
+                Tim3Remap::None => 0b00,
+                Tim3Remap::Partial => 0b10,
+                Tim3Remap::Full => 0b11,
+            });
+            if remap.tim4 {
+                mapr.tim4_remap().set(&mut val);
+            }
+            if remap.pd01 {
+                mapr.pd01_remap().set(&mut val);
+            }
+            mapr.swj_cfg().write(&mut val, match remap.swj {
+                SwjCfg::Full => 0b000,
+                SwjCfg::NoNjtrst => 0b001,
+                SwjCfg::SwdOnly => 0b010,
+                SwjCfg::Disabled => 0b100,
+            });
+            val
+        });
+        AfioRemapped {
+            spi1_not_remapped: Some(Spi1NotRemapped(())).filter(|_| !remap.spi1),
+            spi1: Some(Spi1Remapped(())).filter(|_| remap.spi1),
+            usart1_not_remapped: Some(Usart1NotRemapped(())).filter(|_| !remap.usart1),
+            usart1: Some(Usart1Remapped(())).filter(|_| remap.usart1),
+            usart2_not_remapped: Some(Usart2NotRemapped(())).filter(|_| !remap.usart2),
+            usart2: Some(Usart2Remapped(())).filter(|_| remap.usart2),
+            usart3_not_remapped: match remap.usart3 {
+                Usart3Remap::None => Some(Usart3NotRemapped(())),
+                Usart3Remap::Partial | Usart3Remap::Full => None,
+            },
+            usart3_partial: match remap.usart3 {
+                Usart3Remap::Partial => Some(Usart3PartialRemapped(())),
+                Usart3Remap::None | Usart3Remap::Full => None,
+            },
+            usart3_full: match remap.usart3 {
+                Usart3Remap::Full => Some(Usart3FullRemapped(())),
+                Usart3Remap::None | Usart3Remap::Partial => None,
+            },
+        }
+    }
+
+    /// Gives back the proofs returned by [`AfioEn::set_remap`], so the pins
+    /// remapping can be changed again.
+    ///
+    /// # Panics
+    ///
+    /// If some of the proofs were already taken.
+    pub fn release_remap(&mut self, remapped: AfioRemapped) {
+        if !remapped.is_complete() {
+            panic!("AFIO remap proofs are in use");
+        }
+        self.remapped = false;
+    }
+}
+
+impl AfioRemapped {
+    fn is_complete(&self) -> bool {
+        (self.spi1_not_remapped.is_some() || self.spi1.is_some())
+            && (self.usart1_not_remapped.is_some() || self.usart1.is_some())
+            && (self.usart2_not_remapped.is_some() || self.usart2.is_some())
+            && (self.usart3_not_remapped.is_some()
+                || self.usart3_partial.is_some()
+                || self.usart3_full.is_some())
+    }
+}
+
+impl inventory::Item for AfioEn {
+    fn teardown(&mut self, _token: &mut inventory::GuardToken<Self>) {
+        self.periph.rcc_apb2enr_afioen.clear_bit()
+    }
+}
+
+impl Default for AfioRemap {
+    fn default() -> Self {
+        Self {
+            spi1: false,
+            i2c1: false,
+            usart1: false,
+            usart2: false,
+            usart3: Usart3Remap::None,
+            tim1: Tim1Remap::None,
+            tim2: Tim2Remap::None,
+            tim3: Tim3Remap::None,
+            tim4: false,
+            pd01: false,
+            swj: SwjCfg::Full,
+        }
+    }
+}
@@ -12,12 +12,20 @@
     )
 ))]
 use crate::dma::mux::DmamuxChEn;
-#[cfg(feature = "dma")]
-use crate::dma::DmaChEn;
 #[allow(unused_imports)]
 use drone_cortex_m::thr::prelude::*;
+
 #[cfg(feature = "dma")]
-use drone_stm32_map::periph::dma::ch::DmaChMap;
+dma_ch! {
+    use crate::dma::DmaChEn;
+    use drone_stm32_map::periph::dma::ch::DmaChMap;
+}
+
+#[cfg(feature = "dma")]
+dma_st! {
+    use crate::dma::DmaStEn;
+    use drone_stm32_map::periph::dma::st::DmaStMap;
+}
 
 /// Driver reset and clock control.
 pub trait DrvRcc {
@@ -39,45 +47,35 @@ pub trait DrvClockSel {
     fn clock_sel(&self, value: u32);
 }
 
-/// Driver DMA receiver.
-#[cfg(feature = "dma")]
-pub trait DrvDmaRx<Rx: DmaChMap> {
-    /// Initializes peripheral address of the DMA channel to the receiver.
-    fn dma_rx_paddr_init(&self, dma_rx: &DmaChEn<Rx, impl IntToken>);
-
-    #[cfg(any(
+/// DMA receiver request of the peripheral, which can be served by the DMA
+/// channel `Rx`.
+#[cfg(all(
+    feature = "dma",
+    any(
+        stm32_mcu = "stm32l4x1",
+        stm32_mcu = "stm32l4x2",
+        stm32_mcu = "stm32l4x3",
+        stm32_mcu = "stm32l4x5",
+        stm32_mcu = "stm32l4x6",
         stm32_mcu = "stm32l4r5",
         stm32_mcu = "stm32l4r7",
         stm32_mcu = "stm32l4r9",
         stm32_mcu = "stm32l4s5",
         stm32_mcu = "stm32l4s7",
         stm32_mcu = "stm32l4s9"
-    ))]
-    /// Initializes the DMA channel as a receiver.
-    fn dma_rx_init(
-        &self,
-        dma_rx: &DmaChEn<Rx, impl IntToken>,
-        dmamux_rx: &DmamuxChEn<Rx::DmamuxChMap>,
-        rx_dma_req_id: u32,
-    ) {
-        self.dma_rx_paddr_init(dma_rx);
-        dmamux_rx.set_dma_req_id(rx_dma_req_id);
-    }
-
-    #[cfg(any(
-        stm32_mcu = "stm32l4x1",
-        stm32_mcu = "stm32l4x2",
-        stm32_mcu = "stm32l4x3",
-        stm32_mcu = "stm32l4x5",
-        stm32_mcu = "stm32l4x6"
-    ))]
-    /// Initializes the DMA channel as a receiver.
-    fn dma_rx_init(&self, dma_rx: &DmaChEn<Rx, impl IntToken>, dma_rx_ch: u32) {
-        self.dma_rx_paddr_init(dma_rx);
-        dma_rx.ch_select(dma_rx_ch);
-    }
+    )
+))]
+pub trait DmaRxReq<Rx: DmaChMap> {
+    /// `DMA_CSELR` channel selection on STM32L4x1–x6, or DMAMUX request ID on
+    /// STM32L4R/S.
+    const DMA_RX_REQ: u32;
+}
 
-    #[cfg(not(any(
+/// DMA transmitter request of the peripheral, which can be served by the DMA
+/// channel `Tx`.
+#[cfg(all(
+    feature = "dma",
+    any(
         stm32_mcu = "stm32l4x1",
         stm32_mcu = "stm32l4x2",
         stm32_mcu = "stm32l4x3",
@@ -89,66 +87,236 @@ pub trait DrvDmaRx<Rx: DmaChMap> {
         stm32_mcu = "stm32l4s5",
         stm32_mcu = "stm32l4s7",
         stm32_mcu = "stm32l4s9"
-    )))]
-    /// Initializes the DMA channel as a receiver.
-    fn dma_rx_init(&self, dma_rx: &DmaChEn<Rx, impl IntToken>) {
-        self.dma_rx_paddr_init(dma_rx);
-    }
+    )
+))]
+pub trait DmaTxReq<Tx: DmaChMap> {
+    /// `DMA_CSELR` channel selection on STM32L4x1–x6, or DMAMUX request ID on
+    /// STM32L4R/S.
+    const DMA_TX_REQ: u32;
 }
 
-/// Driver DMA transmitter.
 #[cfg(feature = "dma")]
-pub trait DrvDmaTx<Tx: DmaChMap> {
-    /// Initializes peripheral address of the DMA channel to the transmitter.
-    fn dma_tx_paddr_init(&self, dma_tx: &DmaChEn<Tx, impl IntToken>);
+dma_ch! {
+    /// Driver DMA receiver.
+    pub trait DrvDmaRx<Rx: DmaChMap> {
+        /// Initializes peripheral address of the DMA channel to the receiver.
+        fn dma_rx_paddr_init(&self, dma_rx: &DmaChEn<Rx, impl IntToken>);
 
-    #[cfg(any(
-        stm32_mcu = "stm32l4r5",
-        stm32_mcu = "stm32l4r7",
-        stm32_mcu = "stm32l4r9",
-        stm32_mcu = "stm32l4s5",
-        stm32_mcu = "stm32l4s7",
-        stm32_mcu = "stm32l4s9"
-    ))]
-    /// Initializes the DMA channel as a transmitter.
-    fn dma_tx_init(
-        &self,
-        dma_tx: &DmaChEn<Tx, impl IntToken>,
-        dmamux_tx: &DmamuxChEn<Tx::DmamuxChMap>,
-        tx_dma_req_id: u32,
-    ) {
-        self.dma_tx_paddr_init(dma_tx);
-        dmamux_tx.set_dma_req_id(tx_dma_req_id);
+        #[cfg(any(
+            stm32_mcu = "stm32l4r5",
+            stm32_mcu = "stm32l4r7",
+            stm32_mcu = "stm32l4r9",
+            stm32_mcu = "stm32l4s5",
+            stm32_mcu = "stm32l4s7",
+            stm32_mcu = "stm32l4s9"
+        ))]
+        /// Initializes the DMA channel as a receiver.
+        fn dma_rx_init(
+            &self,
+            dma_rx: &DmaChEn<Rx, impl IntToken>,
+            dmamux_rx: &DmamuxChEn<Rx::DmamuxChMap>,
+        ) where
+            Self: DmaRxReq<Rx>,
+        {
+            self.dma_rx_paddr_init(dma_rx);
+            dmamux_rx.set_dma_req_id(Self::DMA_RX_REQ);
+        }
+
+        #[cfg(any(
+            stm32_mcu = "stm32l4x1",
+            stm32_mcu = "stm32l4x2",
+            stm32_mcu = "stm32l4x3",
+            stm32_mcu = "stm32l4x5",
+            stm32_mcu = "stm32l4x6"
+        ))]
+        /// Initializes the DMA channel as a receiver.
+        fn dma_rx_init(&self, dma_rx: &DmaChEn<Rx, impl IntToken>)
+        where
+            Self: DmaRxReq<Rx>,
+        {
+            self.dma_rx_paddr_init(dma_rx);
+            dma_rx.ch_select(Self::DMA_RX_REQ);
+        }
+
+        #[cfg(any(
+            stm32_mcu = "stm32l4r5",
+            stm32_mcu = "stm32l4r7",
+            stm32_mcu = "stm32l4r9",
+            stm32_mcu = "stm32l4s5",
+            stm32_mcu = "stm32l4s7",
+            stm32_mcu = "stm32l4s9"
+        ))]
+        /// Initializes the DMA channel as a receiver with the raw DMAMUX request
+        /// ID `rx_dma_req_id`.
+        ///
+        /// The request ID isn't checked against the [`DmaRxReq`] tables.
+        fn dma_rx_init_raw(
+            &self,
+            dma_rx: &DmaChEn<Rx, impl IntToken>,
+            dmamux_rx: &DmamuxChEn<Rx::DmamuxChMap>,
+            rx_dma_req_id: u32,
+        ) {
+            self.dma_rx_paddr_init(dma_rx);
+            dmamux_rx.set_dma_req_id(rx_dma_req_id);
+        }
+
+        #[cfg(any(
+            stm32_mcu = "stm32l4x1",
+            stm32_mcu = "stm32l4x2",
+            stm32_mcu = "stm32l4x3",
+            stm32_mcu = "stm32l4x5",
+            stm32_mcu = "stm32l4x6"
+        ))]
+        /// Initializes the DMA channel as a receiver with the raw `DMA_CSELR`
+        /// channel selection `dma_rx_ch`.
+        ///
+        /// The channel selection isn't checked against the [`DmaRxReq`] tables.
+        fn dma_rx_init_raw(&self, dma_rx: &DmaChEn<Rx, impl IntToken>, dma_rx_ch: u32) {
+            self.dma_rx_paddr_init(dma_rx);
+            dma_rx.ch_select(dma_rx_ch);
+        }
+
+        #[cfg(not(any(
+            stm32_mcu = "stm32l4x1",
+            stm32_mcu = "stm32l4x2",
+            stm32_mcu = "stm32l4x3",
+            stm32_mcu = "stm32l4x5",
+            stm32_mcu = "stm32l4x6",
+            stm32_mcu = "stm32l4r5",
+            stm32_mcu = "stm32l4r7",
+            stm32_mcu = "stm32l4r9",
+            stm32_mcu = "stm32l4s5",
+            stm32_mcu = "stm32l4s7",
+            stm32_mcu = "stm32l4s9"
+        )))]
+        /// Initializes the DMA channel as a receiver.
+        fn dma_rx_init(&self, dma_rx: &DmaChEn<Rx, impl IntToken>) {
+            self.dma_rx_paddr_init(dma_rx);
+        }
     }
 
-    #[cfg(any(
-        stm32_mcu = "stm32l4x1",
-        stm32_mcu = "stm32l4x2",
-        stm32_mcu = "stm32l4x3",
-        stm32_mcu = "stm32l4x5",
-        stm32_mcu = "stm32l4x6"
-    ))]
-    /// Initializes the DMA channel as a transmitter.
-    fn dma_tx_init(&self, dma_tx: &DmaChEn<Tx, impl IntToken>, dma_tx_ch: u32) {
-        self.dma_tx_paddr_init(dma_tx);
-        dma_tx.ch_select(dma_tx_ch);
+    /// Driver DMA transmitter.
+    pub trait DrvDmaTx<Tx: DmaChMap> {
+        /// Initializes peripheral address of the DMA channel to the transmitter.
+        fn dma_tx_paddr_init(&self, dma_tx: &DmaChEn<Tx, impl IntToken>);
+
+        #[cfg(any(
+            stm32_mcu = "stm32l4r5",
+            stm32_mcu = "stm32l4r7",
+            stm32_mcu = "stm32l4r9",
+            stm32_mcu = "stm32l4s5",
+            stm32_mcu = "stm32l4s7",
+            stm32_mcu = "stm32l4s9"
+        ))]
+        /// Initializes the DMA channel as a transmitter.
+        fn dma_tx_init(
+            &self,
+            dma_tx: &DmaChEn<Tx, impl IntToken>,
+            dmamux_tx: &DmamuxChEn<Tx::DmamuxChMap>,
+        ) where
+            Self: DmaTxReq<Tx>,
+        {
+            self.dma_tx_paddr_init(dma_tx);
+            dmamux_tx.set_dma_req_id(Self::DMA_TX_REQ);
+        }
+
+        #[cfg(any(
+            stm32_mcu = "stm32l4x1",
+            stm32_mcu = "stm32l4x2",
+            stm32_mcu = "stm32l4x3",
+            stm32_mcu = "stm32l4x5",
+            stm32_mcu = "stm32l4x6"
+        ))]
+        /// Initializes the DMA channel as a transmitter.
+        fn dma_tx_init(&self, dma_tx: &DmaChEn<Tx, impl IntToken>)
+        where
+            Self: DmaTxReq<Tx>,
+        {
+            self.dma_tx_paddr_init(dma_tx);
+            dma_tx.ch_select(Self::DMA_TX_REQ);
+        }
+
+        #[cfg(any(
+            stm32_mcu = "stm32l4r5",
+            stm32_mcu = "stm32l4r7",
+            stm32_mcu = "stm32l4r9",
+            stm32_mcu = "stm32l4s5",
+            stm32_mcu = "stm32l4s7",
+            stm32_mcu = "stm32l4s9"
+        ))]
+        /// Initializes the DMA channel as a transmitter with the raw DMAMUX request
+        /// ID `tx_dma_req_id`.
+        ///
+        /// The request ID isn't checked against the [`DmaTxReq`] tables.
+        fn dma_tx_init_raw(
+            &self,
+            dma_tx: &DmaChEn<Tx, impl IntToken>,
+            dmamux_tx: &DmamuxChEn<Tx::DmamuxChMap>,
+            tx_dma_req_id: u32,
+        ) {
+            self.dma_tx_paddr_init(dma_tx);
+            dmamux_tx.set_dma_req_id(tx_dma_req_id);
+        }
+
+        #[cfg(any(
+            stm32_mcu = "stm32l4x1",
+            stm32_mcu = "stm32l4x2",
+            stm32_mcu = "stm32l4x3",
+            stm32_mcu = "stm32l4x5",
+            stm32_mcu = "stm32l4x6"
+        ))]
+        /// Initializes the DMA channel as a transmitter with the raw `DMA_CSELR`
+        /// channel selection `dma_tx_ch`.
+        ///
+        /// The channel selection isn't checked against the [`DmaTxReq`] tables.
+        fn dma_tx_init_raw(&self, dma_tx: &DmaChEn<Tx, impl IntToken>, dma_tx_ch: u32) {
+            self.dma_tx_paddr_init(dma_tx);
+            dma_tx.ch_select(dma_tx_ch);
+        }
+
+        #[cfg(not(any(
+            stm32_mcu = "stm32l4x1",
+            stm32_mcu = "stm32l4x2",
+            stm32_mcu = "stm32l4x3",
+            stm32_mcu = "stm32l4x5",
+            stm32_mcu = "stm32l4x6",
+            stm32_mcu = "stm32l4r5",
+            stm32_mcu = "stm32l4r7",
+            stm32_mcu = "stm32l4r9",
+            stm32_mcu = "stm32l4s5",
+            stm32_mcu = "stm32l4s7",
+            stm32_mcu = "stm32l4s9"
+        )))]
+        /// Initializes the DMA channel as a transmitter.
+        fn dma_tx_init(&self, dma_tx: &DmaChEn<Tx, impl IntToken>) {
+            self.dma_tx_paddr_init(dma_tx);
+        }
     }
+}
 
-    #[cfg(not(any(
-        stm32_mcu = "stm32l4x1",
-        stm32_mcu = "stm32l4x2",
-        stm32_mcu = "stm32l4x3",
-        stm32_mcu = "stm32l4x5",
-        stm32_mcu = "stm32l4x6",
-        stm32_mcu = "stm32l4r5",
-        stm32_mcu = "stm32l4r7",
-        stm32_mcu = "stm32l4r9",
-        stm32_mcu = "stm32l4s5",
-        stm32_mcu = "stm32l4s7",
-        stm32_mcu = "stm32l4s9"
-    )))]
-    /// Initializes the DMA channel as a transmitter.
-    fn dma_tx_init(&self, dma_tx: &DmaChEn<Tx, impl IntToken>) {
-        self.dma_tx_paddr_init(dma_tx);
+#[cfg(feature = "dma")]
+dma_st! {
+    /// Driver DMA receiver.
+    pub trait DrvDmaRx<Rx: DmaStMap> {
+        /// Initializes peripheral address of the DMA stream to the receiver.
+        fn dma_rx_paddr_init(&self, dma_rx: &DmaStEn<Rx, impl IntToken>);
+
+        /// Initializes the DMA stream as a receiver. The request channel is
+        /// selected with [`DmaStConfig::ch`](crate::dma::DmaStConfig::ch).
+        fn dma_rx_init(&self, dma_rx: &DmaStEn<Rx, impl IntToken>) {
+            self.dma_rx_paddr_init(dma_rx);
+        }
+    }
+
+    /// Driver DMA transmitter.
+    pub trait DrvDmaTx<Tx: DmaStMap> {
+        /// Initializes peripheral address of the DMA stream to the transmitter.
+        fn dma_tx_paddr_init(&self, dma_tx: &DmaStEn<Tx, impl IntToken>);
+
+        /// Initializes the DMA stream as a transmitter. The request channel is
+        /// selected with [`DmaStConfig::ch`](crate::dma::DmaStConfig::ch).
+        fn dma_tx_init(&self, dma_tx: &DmaStEn<Tx, impl IntToken>) {
+            self.dma_tx_paddr_init(dma_tx);
+        }
     }
 }
@@ -0,0 +1,118 @@
+use super::DmaSize;
+
+/// DMA data element.
+///
+/// # Safety
+///
+/// `SIZE` must be the size of the type.
+pub unsafe trait DmaWord: Copy + Send + 'static {
+    #[doc(hidden)]
+    const SIZE: DmaSize;
+}
+
+/// Memory buffer, which can be read by DMA.
+///
+/// # Safety
+///
+/// The memory region returned by [`DmaReadBuffer::dma_read_buffer`] must stay
+/// valid and must not move, as long as the buffer value is alive, even if the
+/// value itself is moved.
+pub unsafe trait DmaReadBuffer: Send + 'static {
+    /// Data element type.
+    type Word: DmaWord;
+
+    /// Returns the address and the number of elements of the buffer.
+    fn dma_read_buffer(&self) -> (*const Self::Word, usize);
+}
+
+/// Memory buffer, which can be written by DMA.
+///
+/// # Safety
+///
+/// The memory region returned by [`DmaWriteBuffer::dma_write_buffer`] must
+/// stay valid and must not move, as long as the buffer value is alive, even if
+/// the value itself is moved.
+pub unsafe trait DmaWriteBuffer: Send + 'static {
+    /// Data element type.
+    type Word: DmaWord;
+
+    /// Returns the address and the number of elements of the buffer.
+    fn dma_write_buffer(&mut self) -> (*mut Self::Word, usize);
+}
+
+/// Half of a circular DMA buffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DmaHalf {
+    /// The first half of the buffer.
+    First,
+    /// The second half of the buffer.
+    Second,
+}
+
+unsafe impl DmaWord for u8 {
+    const SIZE: DmaSize = DmaSize::Bits8;
+}
+
+unsafe impl DmaWord for u16 {
+    const SIZE: DmaSize = DmaSize::Bits16;
+}
+
+unsafe impl DmaWord for u32 {
+    const SIZE: DmaSize = DmaSize::Bits32;
+}
+
+unsafe impl<W: DmaWord + Sync> DmaReadBuffer for &'static [W] {
+    type Word = W;
+
+    fn dma_read_buffer(&self) -> (*const W, usize) {
+        (self.as_ptr(), self.len())
+    }
+}
+
+unsafe impl<W: DmaWord> DmaReadBuffer for &'static mut [W] {
+    type Word = W;
+
+    fn dma_read_buffer(&self) -> (*const W, usize) {
+        (self.as_ptr(), self.len())
+    }
+}
+
+unsafe impl<W: DmaWord> DmaWriteBuffer for &'static mut [W] {
+    type Word = W;
+
+    fn dma_write_buffer(&mut self) -> (*mut W, usize) {
+        (self.as_mut_ptr(), self.len())
+    }
+}
+
+unsafe impl<W: DmaWord> DmaReadBuffer for Box<[W]> {
+    type Word = W;
+
+    fn dma_read_buffer(&self) -> (*const W, usize) {
+        (self.as_ptr(), self.len())
+    }
+}
+
+unsafe impl<W: DmaWord> DmaWriteBuffer for Box<[W]> {
+    type Word = W;
+
+    fn dma_write_buffer(&mut self) -> (*mut W, usize) {
+        (self.as_mut_ptr(), self.len())
+    }
+}
+
+unsafe impl<W: DmaWord> DmaReadBuffer for Vec<W> {
+    type Word = W;
+
+    fn dma_read_buffer(&self) -> (*const W, usize) {
+        (self.as_ptr(), self.len())
+    }
+}
+
+unsafe impl<W: DmaWord> DmaWriteBuffer for Vec<W> {
+    type Word = W;
+
+    fn dma_write_buffer(&mut self) -> (*mut W, usize) {
+        (self.as_mut_ptr(), self.len())
+    }
+}
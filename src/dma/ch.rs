@@ -1,14 +1,9 @@
-use super::DmaEn;
-use core::fmt;
+use super::{DmaCircularError, DmaDir, DmaEn, DmaHalf, DmaPriority, DmaSize, DmaTransferError};
 use drone_core::inventory;
 use drone_cortex_m::{fib, reg::prelude::*, thr::prelude::*};
 use drone_stm32_map::periph::dma::ch::{traits::*, DmaChMap, DmaChPeriph};
 use futures::prelude::*;
 
-/// Error returned when `DMA_ISR_TEIFx` flag in set.
-#[derive(Debug)]
-pub struct DmaTransferError;
-
 /// DMA channel driver.
 pub struct DmaCh<T: DmaChMap, I: IntToken>(DmaChEn<T, I>);
 
@@ -173,6 +168,14 @@ impl<T: DmaChMap, I: IntToken> DmaChEn<T, I> {
         });
     }
 
+    /// Disables the channel and clears its interrupt flags. Returns the number
+    /// of data left to transfer.
+    pub fn abort(&self) -> usize {
+        self.periph.dma_ccr.en().clear_bit();
+        self.periph.dma_ifcr_cgif.set_bit_band();
+        self.size()
+    }
+
     /// Returns a future, which resolves on DMA transfer complete event.
     pub fn transfer_complete(&self) -> impl Future<Output = Result<(), DmaTransferError>> {
         let teif = self.periph.dma_isr_teif;
@@ -210,6 +213,48 @@ impl<T: DmaChMap, I: IntToken> DmaChEn<T, I> {
             }
         }))
     }
+
+    pub(super) fn poll_complete(&self) -> Option<Result<(), DmaTransferError>> {
+        if self.periph.dma_isr_teif.read_bit_band() {
+            self.periph.dma_ifcr_cgif.set_bit_band();
+            Some(Err(DmaTransferError))
+        } else if self.periph.dma_isr_tcif.read_bit_band() {
+            self.periph.dma_ifcr_ctcif.set_bit_band();
+            Some(Ok(()))
+        } else {
+            None
+        }
+    }
+
+    pub(super) fn half_stream(
+        &self,
+        capacity: usize,
+    ) -> impl Stream<Item = Result<DmaHalf, DmaCircularError>> + Send {
+        let teif = self.periph.dma_isr_teif;
+        let htif = self.periph.dma_isr_htif;
+        let tcif = self.periph.dma_isr_tcif;
+        let cgif = self.periph.dma_ifcr_cgif;
+        let chtif = self.periph.dma_ifcr_chtif;
+        let ctcif = self.periph.dma_ifcr_ctcif;
+        self.int.add_stream_ring(
+            capacity,
+            |_| Err(DmaCircularError::Overrun),
+            fib::new_fn(move || {
+                if teif.read_bit_band() {
+                    cgif.set_bit_band();
+                    fib::Complete(Err(DmaCircularError::Transfer(DmaTransferError)))
+                } else if htif.read_bit_band() {
+                    chtif.set_bit_band();
+                    fib::Yielded(Some(DmaHalf::First))
+                } else if tcif.read_bit_band() {
+                    ctcif.set_bit_band();
+                    fib::Yielded(Some(DmaHalf::Second))
+                } else {
+                    fib::Yielded(None)
+                }
+            }),
+        )
+    }
 }
 
 #[allow(missing_docs)]
@@ -225,8 +270,132 @@ impl<T: DmaChMap, I: IntToken> DmaChEn<T, I> {
     }
 }
 
-impl fmt::Display for DmaTransferError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "DMA transfer error.")
+/// DMA channel configuration builder.
+///
+/// Produces a `DMA_CCR` value with the channel disabled. Every option is
+/// disabled by default, the direction is [`DmaDir::PeriphToMem`], the sizes
+/// are [`DmaSize::Bits8`], and the priority is [`DmaPriority::Low`].
+pub struct DmaChConfig<'a, T: DmaChMap> {
+    ccr: &'a T::SDmaCcr,
+    val: T::DmaCcrVal,
+}
+
+impl<T: DmaChMap, I: IntToken> DmaChEn<T, I> {
+    /// Returns a new channel configuration builder.
+    pub fn config(&self) -> DmaChConfig<'_, T> {
+        let ccr = self.ccr();
+        DmaChConfig {
+            ccr,
+            val: ccr.default_val(),
+        }
+    }
+}
+
+impl<'a, T: DmaChMap> DmaChConfig<'a, T> {
+    /// Sets the transfer direction.
+    pub fn dir(mut self, dir: DmaDir) -> Self {
+        match dir {
+            DmaDir::PeriphToMem => {
+                self.ccr.mem2mem().clear(&mut self.val);
+                self.ccr.dir().clear(&mut self.val);
+            }
+            DmaDir::MemToPeriph => {
+                self.ccr.mem2mem().clear(&mut self.val);
+                self.ccr.dir().set(&mut self.val);
+            }
+            DmaDir::MemToMem => {
+                self.ccr.mem2mem().set(&mut self.val);
+                self.ccr.dir().set(&mut self.val);
+            }
+        }
+        self
+    }
+
+    /// Sets the memory data element size.
+    pub fn msize(mut self, size: DmaSize) -> Self {
+        self.ccr.msize().write(&mut self.val, size.bits());
+        self
+    }
+
+    /// Sets the peripheral data element size.
+    pub fn psize(mut self, size: DmaSize) -> Self {
+        self.ccr.psize().write(&mut self.val, size.bits());
+        self
+    }
+
+    /// Enables or disables the memory address increment.
+    pub fn minc(mut self, enable: bool) -> Self {
+        if enable {
+            self.ccr.minc().set(&mut self.val);
+        } else {
+            self.ccr.minc().clear(&mut self.val);
+        }
+        self
+    }
+
+    /// Enables or disables the peripheral address increment.
+    pub fn pinc(mut self, enable: bool) -> Self {
+        if enable {
+            self.ccr.pinc().set(&mut self.val);
+        } else {
+            self.ccr.pinc().clear(&mut self.val);
+        }
+        self
+    }
+
+    /// Sets the channel priority level.
+    pub fn priority(mut self, priority: DmaPriority) -> Self {
+        self.ccr.pl().write(&mut self.val, match priority {
+            DmaPriority::Low => 0b00,
+            DmaPriority::Medium => 0b01,
+            DmaPriority::High => 0b10,
+            DmaPriority::VeryHigh => 0b11,
+        });
+        self
+    }
+
+    /// Enables or disables the circular mode.
+    pub fn circ(mut self, enable: bool) -> Self {
+        if enable {
+            self.ccr.circ().set(&mut self.val);
+        } else {
+            self.ccr.circ().clear(&mut self.val);
+        }
+        self
+    }
+
+    /// Enables or disables the transfer error interrupt.
+    pub fn teie(mut self, enable: bool) -> Self {
+        if enable {
+            self.ccr.teie().set(&mut self.val);
+        } else {
+            self.ccr.teie().clear(&mut self.val);
+        }
+        self
+    }
+
+    /// Enables or disables the half transfer interrupt.
+    pub fn htie(mut self, enable: bool) -> Self {
+        if enable {
+            self.ccr.htie().set(&mut self.val);
+        } else {
+            self.ccr.htie().clear(&mut self.val);
+        }
+        self
+    }
+
+    /// Enables or disables the transfer complete interrupt.
+    pub fn tcie(mut self, enable: bool) -> Self {
+        if enable {
+            self.ccr.tcie().set(&mut self.val);
+        } else {
+            self.ccr.tcie().clear(&mut self.val);
+        }
+        self
+    }
+
+    /// Returns the `DMA_CCR` value.
+    pub fn build(self) -> T::DmaCcrVal {
+        self.val
     }
 }
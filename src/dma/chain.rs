@@ -0,0 +1,331 @@
+use super::{DmaChEn, DmaDir, DmaReadBuffer, DmaSize, DmaTransferError, DmaWord, DmaWriteBuffer};
+use core::{
+    convert::Infallible,
+    fmt,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use drone_core::sync::spsc::ring;
+use drone_cortex_m::{fib, reg::prelude::*, thr::prelude::*};
+use drone_stm32_map::periph::dma::ch::{traits::*, DmaChMap};
+use futures::{prelude::*, task::noop_waker_ref};
+
+/// DMA transfer descriptor.
+///
+/// The descriptor owns the memory buffer of a single transfer in a
+/// [`DmaChain`].
+pub struct DmaDesc<B> {
+    buf: B,
+    addr: usize,
+    len: usize,
+    size: DmaSize,
+    dir: DmaDir,
+}
+
+/// Event of a [`DmaChain`].
+pub enum DmaChainEvent<T: DmaChMap, I: IntToken, B> {
+    /// A transfer is complete and the next one, if any, is already started.
+    /// Gives back the buffer of the completed transfer.
+    Done(B),
+    /// The chain is closed and its last transfer is complete. Gives back the
+    /// channel.
+    Finished(DmaChEn<T, I>),
+    /// A transfer failed and the chain is aborted. Gives back the channel, the
+    /// buffer of the failed transfer, followed by the buffers of the transfers,
+    /// which weren't started.
+    Failed(DmaChEn<T, I>, Vec<B>),
+}
+
+/// DMA chain events overflow.
+///
+/// The events weren't consumed in time, and the event, which didn't fit, is
+/// lost along with its buffer.
+#[derive(Debug)]
+pub struct DmaChainOverflow;
+
+/// Queue of DMA transfers, which are started back-to-back from the DMA
+/// interrupt.
+///
+/// The chain is a stream of [`DmaChainEvent`]s. The chain stays idle when its
+/// queue drains, until [`DmaChain::close`] is called. Dropping the chain aborts
+/// the channel.
+#[must_use]
+pub struct DmaChain<T: DmaChMap, I: IntToken, B> {
+    int: I,
+    queue: Option<ring::Sender<DmaDesc<B>, Infallible>>,
+    stream: Option<Pin<Box<dyn Stream<Item = DmaChainItem<T, I, B>> + Send>>>,
+}
+
+type DmaChainItem<T, I, B> = Result<DmaChainEvent<T, I, B>, DmaChainOverflow>;
+
+struct DmaChainState<T: DmaChMap, I: IntToken, B> {
+    ch: Option<DmaChEn<T, I>>,
+    ccr_val: T::DmaCcrVal,
+    current: Option<DmaDesc<B>>,
+    pending: Vec<DmaDesc<B>>,
+    queue: Option<ring::Receiver<DmaDesc<B>, Infallible>>,
+}
+
+impl<B: DmaWriteBuffer> DmaDesc<B> {
+    /// Creates a new peripheral-to-memory transfer descriptor into `buf`.
+    pub fn rx(mut buf: B) -> Self {
+        let (addr, len) = buf.dma_write_buffer();
+        Self {
+            buf,
+            addr: addr as usize,
+            len,
+            size: B::Word::SIZE,
+            dir: DmaDir::PeriphToMem,
+        }
+    }
+}
+
+impl<B: DmaReadBuffer> DmaDesc<B> {
+    /// Creates a new memory-to-peripheral transfer descriptor from `buf`.
+    pub fn tx(buf: B) -> Self {
+        let (addr, len) = buf.dma_read_buffer();
+        Self {
+            buf,
+            addr: addr as usize,
+            len,
+            size: B::Word::SIZE,
+            dir: DmaDir::MemToPeriph,
+        }
+    }
+}
+
+impl<B> DmaDesc<B> {
+    /// Limits the transfer to the first `len` elements of the buffer.
+    ///
+    /// # Panics
+    ///
+    /// If `len` is greater than the buffer length.
+    pub fn with_len(mut self, len: usize) -> Self {
+        if len > self.len {
+            panic!("DMA descriptor length is out of the buffer");
+        }
+        self.len = len;
+        self
+    }
+
+    /// Returns the transfer direction.
+    pub fn dir(&self) -> DmaDir {
+        self.dir
+    }
+
+    /// Releases the buffer.
+    pub fn into_buf(self) -> B {
+        self.buf
+    }
+}
+
+impl<T: DmaChMap, I: IntToken> DmaChEn<T, I> {
+    /// Starts the chain of transfers described by `descs`. More transfers can
+    /// be appended with [`DmaChain::push`].
+    ///
+    /// The channel is configured with `ccr_val` for each transfer, where the
+    /// memory size, the direction and the interrupt fields are overridden
+    /// according to the descriptor. The next transfer is started from the
+    /// transfer complete interrupt. The peripheral address should be already
+    /// initialized. `capacity` is the number of events, which can be buffered
+    /// before [`DmaChainOverflow`] is reported, and the number of pushed
+    /// descriptors, which can wait for their transfers.
+    pub fn start_chain<B: Send + 'static>(
+        self,
+        descs: Vec<DmaDesc<B>>,
+        ccr_val: T::DmaCcrVal,
+        capacity: usize,
+    ) -> DmaChain<T, I, B> {
+        let int = *self.int();
+        let (queue_tx, queue_rx) = ring::channel(capacity);
+        let mut pending = descs;
+        pending.reverse();
+        let mut state = DmaChainState {
+            ch: Some(self),
+            ccr_val,
+            current: None,
+            pending,
+            queue: Some(queue_rx),
+        };
+        let stream = int.add_stream_ring(
+            capacity,
+            |_| Err(DmaChainOverflow),
+            fib::new_fn(move || {
+                if state.ch.is_none() {
+                    return fib::Complete(Ok(()));
+                }
+                fib::Yielded(state.resume())
+            }),
+        );
+        // The first transfer is started from the interrupt, after the fiber
+        // is attached.
+        int.trigger();
+        DmaChain {
+            int,
+            queue: Some(queue_tx),
+            stream: Some(Box::pin(stream)),
+        }
+    }
+}
+
+impl<T: DmaChMap, I: IntToken, B> DmaChain<T, I, B> {
+    /// Appends the transfer described by `desc` to the chain.
+    ///
+    /// The descriptor is picked up from the DMA interrupt, so a transfer
+    /// pushed before the previous one completes is started back-to-back. If
+    /// the queue is full, or the chain is already closed or failed, gives back
+    /// `desc`.
+    pub fn push(&mut self, desc: DmaDesc<B>) -> Result<(), DmaDesc<B>> {
+        let queue = match &mut self.queue {
+            Some(queue) => queue,
+            None => return Err(desc),
+        };
+        queue.send(desc).map_err(|err| err.value)?;
+        // Starts the transfer if the chain is idle.
+        self.int.trigger();
+        Ok(())
+    }
+
+    /// Closes the chain.
+    ///
+    /// The transfers, which are already pushed, are carried out, and then the
+    /// chain gives back the channel with [`DmaChainEvent::Finished`].
+    pub fn close(&mut self) {
+        drop(self.queue.take());
+        // Finishes the chain if it is idle.
+        self.int.trigger();
+    }
+}
+
+impl<T: DmaChMap, I: IntToken, B> Stream for DmaChain<T, I, B> {
+    type Item = DmaChainItem<T, I, B>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut()
+            .stream
+            .as_mut()
+            .unwrap()
+            .as_mut()
+            .poll_next(cx)
+    }
+}
+
+impl<T: DmaChMap, I: IntToken, B> Unpin for DmaChain<T, I, B> {}
+
+impl<T: DmaChMap, I: IntToken, B> Drop for DmaChain<T, I, B> {
+    fn drop(&mut self) {
+        // The fiber releases the channel on the next interrupt after the
+        // stream is dropped.
+        drop(self.stream.take());
+        self.int.trigger();
+    }
+}
+
+impl<T: DmaChMap, I: IntToken, B> DmaChainState<T, I, B> {
+    fn resume(&mut self) -> Option<DmaChainEvent<T, I, B>> {
+        self.ch.as_ref()?;
+        let desc = match self.current.take() {
+            Some(desc) => desc,
+            None => return self.start_next(),
+        };
+        match self.ch.as_ref().unwrap().poll_complete() {
+            None => {
+                self.current = Some(desc);
+                None
+            }
+            Some(Ok(())) => {
+                let next = self.next_desc();
+                let ch = self.ch.as_ref().unwrap();
+                if let Some(next) = next {
+                    start_desc(ch, &next, self.ccr_val);
+                    self.current = Some(next);
+                } else if self.queue.is_none() {
+                    // The chain is closed, `Finished` follows on the next
+                    // interrupt.
+                    ch.int().trigger();
+                }
+                Some(DmaChainEvent::Done(desc.buf))
+            }
+            Some(Err(DmaTransferError)) => {
+                let ch = self.ch.take().unwrap();
+                ch.abort();
+                let mut bufs = Vec::with_capacity(self.pending.len() + 1);
+                bufs.push(desc.buf);
+                bufs.extend(self.pending.drain(..).rev().map(DmaDesc::into_buf));
+                while let Some(desc) = self.poll_queue() {
+                    bufs.push(desc.buf);
+                }
+                self.queue = None;
+                Some(DmaChainEvent::Failed(ch, bufs))
+            }
+        }
+    }
+
+    fn start_next(&mut self) -> Option<DmaChainEvent<T, I, B>> {
+        if let Some(desc) = self.next_desc() {
+            start_desc(self.ch.as_ref().unwrap(), &desc, self.ccr_val);
+            self.current = Some(desc);
+            None
+        } else if self.queue.is_none() {
+            let ch = self.ch.take().unwrap();
+            ch.abort();
+            Some(DmaChainEvent::Finished(ch))
+        } else {
+            None
+        }
+    }
+
+    fn next_desc(&mut self) -> Option<DmaDesc<B>> {
+        self.pending.pop().or_else(|| self.poll_queue())
+    }
+
+    fn poll_queue(&mut self) -> Option<DmaDesc<B>> {
+        let queue = self.queue.as_mut()?;
+        let mut cx = Context::from_waker(noop_waker_ref());
+        match Pin::new(queue).poll_next(&mut cx) {
+            Poll::Ready(Some(Ok(desc))) => Some(desc),
+            Poll::Ready(Some(Err(never))) => match never {},
+            Poll::Ready(None) => {
+                // The chain is closed.
+                self.queue = None;
+                None
+            }
+            Poll::Pending => None,
+        }
+    }
+}
+
+impl<T: DmaChMap, I: IntToken, B> Drop for DmaChainState<T, I, B> {
+    fn drop(&mut self) {
+        if let Some(ch) = &self.ch {
+            ch.abort();
+        }
+    }
+}
+
+impl fmt::Display for DmaChainOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DMA chain events overflow.")
+    }
+}
+
+fn start_desc<T: DmaChMap, I: IntToken, B>(
+    ch: &DmaChEn<T, I>,
+    desc: &DmaDesc<B>,
+    mut ccr_val: T::DmaCcrVal,
+) {
+    let ccr = ch.ccr();
+    ccr.en().clear_bit();
+    unsafe { ch.set_maddr(desc.addr as *const u8) };
+    ch.set_size(desc.len);
+    ccr.msize().write(&mut ccr_val, desc.size.bits());
+    if let DmaDir::MemToPeriph = desc.dir {
+        ccr.dir().set(&mut ccr_val);
+    } else {
+        ccr.dir().clear(&mut ccr_val);
+    }
+    ccr.teie().set(&mut ccr_val);
+    ccr.tcie().set(&mut ccr_val);
+    ccr.en().set(&mut ccr_val);
+    ccr.store_val(ccr_val);
+}
@@ -0,0 +1,144 @@
+use super::{DmaChEn, DmaCircularError, DmaHalf, DmaWriteBuffer};
+use core::{
+    pin::Pin,
+    slice,
+    task::{Context, Poll},
+};
+use drone_cortex_m::{reg::prelude::*, thr::prelude::*};
+use drone_stm32_map::periph::dma::ch::{traits::*, DmaChMap};
+use futures::{prelude::*, task::noop_waker_ref};
+
+/// Continuous DMA transfer in the circular mode.
+///
+/// The transfer is a stream of buffer halves as they fill. The halves are read
+/// with [`read_half`](DmaCircular::read_half). Dropping the transfer aborts the
+/// channel.
+#[must_use]
+pub struct DmaCircular<T: DmaChMap, I: IntToken, B: DmaWriteBuffer> {
+    inner: Option<(DmaChEn<T, I>, B)>,
+    addr: usize,
+    len: usize,
+    stream: Pin<Box<dyn Stream<Item = Result<DmaHalf, DmaCircularError>> + Send>>,
+    next: Option<Result<DmaHalf, DmaCircularError>>,
+}
+
+impl<T: DmaChMap, I: IntToken> DmaChEn<T, I> {
+    /// Starts a circular peripheral-to-memory transfer into `buf`.
+    ///
+    /// The channel is configured with `ccr_val`, where the circular mode, the
+    /// interrupts, the memory size and the direction fields are overridden.
+    /// The peripheral address should be already initialized. `capacity` is the
+    /// number of filled halves, which can be buffered before
+    /// [`DmaCircularError::Overrun`] is reported.
+    ///
+    /// # Panics
+    ///
+    /// If length of `buf` is odd or greater than 65535.
+    pub fn start_circular_rx<B: DmaWriteBuffer>(
+        self,
+        mut buf: B,
+        mut ccr_val: T::DmaCcrVal,
+        capacity: usize,
+    ) -> DmaCircular<T, I, B> {
+        let (addr, len) = buf.dma_write_buffer();
+        if len % 2 != 0 {
+            panic!("DMA circular buffer length is odd");
+        }
+        let stream = Box::pin(self.half_stream(capacity));
+        self.ccr().circ().set(&mut ccr_val);
+        self.ccr().dir().clear(&mut ccr_val);
+        self.ccr().teie().set(&mut ccr_val);
+        self.ccr().htie().set(&mut ccr_val);
+        self.ccr().tcie().set(&mut ccr_val);
+        self.start(addr as *const B::Word, len, ccr_val);
+        DmaCircular {
+            inner: Some((self, buf)),
+            addr: addr as usize,
+            len,
+            stream,
+            next: None,
+        }
+    }
+}
+
+impl<T: DmaChMap, I: IntToken, B: DmaWriteBuffer> DmaCircular<T, I, B> {
+    /// Returns a reference to the channel.
+    #[inline]
+    pub fn ch(&self) -> &DmaChEn<T, I> {
+        &self.inner.as_ref().unwrap().0
+    }
+
+    /// Calls `f` with the content of the buffer `half`, and checks that the
+    /// half wasn't overwritten in the meantime.
+    ///
+    /// The half is overwritten when the channel reaches it again. If the
+    /// channel is inside the half after `f` returns, or the other half is
+    /// already filled, the result of `f` is discarded and
+    /// [`DmaCircularError::Overrun`] is returned.
+    pub fn read_half<R>(
+        &mut self,
+        half: DmaHalf,
+        f: impl FnOnce(&[B::Word]) -> R,
+    ) -> Result<R, DmaCircularError> {
+        let len = self.len / 2;
+        let offset = match half {
+            DmaHalf::First => 0,
+            DmaHalf::Second => len,
+        };
+        let result =
+            f(unsafe { slice::from_raw_parts((self.addr as *const B::Word).add(offset), len) });
+        // Index of the next item to be written by the channel.
+        let pos = self.len - self.ch().size();
+        if (offset..offset + len).contains(&pos) || self.is_next_ready() {
+            Err(DmaCircularError::Overrun)
+        } else {
+            Ok(result)
+        }
+    }
+
+    /// Stops the channel, giving back the channel and the buffer.
+    pub fn stop(mut self) -> (DmaChEn<T, I>, B) {
+        let (ch, buf) = self.inner.take().unwrap();
+        ch.abort();
+        (ch, buf)
+    }
+}
+
+impl<T: DmaChMap, I: IntToken, B: DmaWriteBuffer> DmaCircular<T, I, B> {
+    /// Returns `true` if another half was filled, keeping the item for the
+    /// next poll of the stream.
+    fn is_next_ready(&mut self) -> bool {
+        if self.next.is_none() {
+            let mut cx = Context::from_waker(noop_waker_ref());
+            if let Poll::Ready(Some(item)) = self.stream.as_mut().poll_next(&mut cx) {
+                self.next = Some(item);
+            }
+        }
+        match self.next {
+            Some(Ok(_)) | Some(Err(DmaCircularError::Overrun)) => true,
+            Some(Err(DmaCircularError::Transfer(_))) | None => false,
+        }
+    }
+}
+
+impl<T: DmaChMap, I: IntToken, B: DmaWriteBuffer> Stream for DmaCircular<T, I, B> {
+    type Item = Result<DmaHalf, DmaCircularError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if let Some(item) = this.next.take() {
+            return Poll::Ready(Some(item));
+        }
+        this.stream.as_mut().poll_next(cx)
+    }
+}
+
+impl<T: DmaChMap, I: IntToken, B: DmaWriteBuffer> Unpin for DmaCircular<T, I, B> {}
+
+impl<T: DmaChMap, I: IntToken, B: DmaWriteBuffer> Drop for DmaCircular<T, I, B> {
+    fn drop(&mut self) {
+        if let Some((ch, _)) = &self.inner {
+            ch.abort();
+        }
+    }
+}
@@ -0,0 +1,44 @@
+/// DMA transfer direction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DmaDir {
+    /// Read from peripheral, write to memory.
+    PeriphToMem,
+    /// Read from memory, write to peripheral.
+    MemToPeriph,
+    /// Read from memory, write to memory pointed by the peripheral address.
+    MemToMem,
+}
+
+/// DMA data element size.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DmaSize {
+    /// 8 bits.
+    Bits8,
+    /// 16 bits.
+    Bits16,
+    /// 32 bits.
+    Bits32,
+}
+
+/// DMA channel priority level.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DmaPriority {
+    /// Low priority.
+    Low,
+    /// Medium priority.
+    Medium,
+    /// High priority.
+    High,
+    /// Very high priority.
+    VeryHigh,
+}
+
+impl DmaSize {
+    pub(super) fn bits(self) -> u32 {
+        match self {
+            Self::Bits8 => 0b00,
+            Self::Bits16 => 0b01,
+            Self::Bits32 => 0b10,
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use core::fmt;
+
+/// Error returned when `DMA_ISR_TEIFx` flag in set.
+#[derive(Debug)]
+pub struct DmaTransferError;
+
+/// DMA circular transfer error.
+#[derive(Debug)]
+pub enum DmaCircularError {
+    /// The consumer didn't keep up with the transfer.
+    Overrun,
+    /// DMA transfer error.
+    Transfer(DmaTransferError),
+}
+
+impl fmt::Display for DmaTransferError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DMA transfer error.")
+    }
+}
+
+impl fmt::Display for DmaCircularError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Overrun => write!(f, "DMA circular buffer overrun."),
+            Self::Transfer(err) => write!(f, "DMA error: {}", err),
+        }
+    }
+}
@@ -15,9 +15,26 @@ use drone_stm32_map::periph::dma::{DmaMap, DmaPeriph};
 ))]
 pub mod mux;
 
-mod ch;
+mod buf;
+mod config;
+mod error;
 
-pub use self::ch::*;
+dma_ch! {
+    mod ch;
+    mod chain;
+    mod circular;
+    mod transfer;
+
+    pub use self::{ch::*, chain::*, circular::*, transfer::*};
+}
+
+dma_st! {
+    mod st;
+
+    pub use self::st::*;
+}
+
+pub use self::{buf::*, config::*, error::*};
 
 /// DMA head driver.
 pub struct Dma<T: DmaMap>(Inventory0<DmaEn<T>>);
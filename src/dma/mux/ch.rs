@@ -1,8 +1,24 @@
-use super::DmamuxEn;
+use super::{DmamuxEn, DmamuxPolarity};
 use drone_core::inventory;
 use drone_cortex_m::reg::prelude::*;
 use drone_stm32_map::periph::dma::mux::ch::{traits::*, DmamuxChMap, DmamuxChPeriph};
 
+/// DMAMUX channel synchronization configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DmamuxSyncConfig {
+    /// Synchronization input signal ID.
+    pub sync_id: u32,
+    /// Synchronization input edges.
+    pub polarity: DmamuxPolarity,
+    /// Number of DMA requests forwarded per synchronization event, from 1 to
+    /// 32.
+    pub nbreq: u32,
+    /// Generates an event on the channel output after `nbreq` requests.
+    pub event_generation: bool,
+    /// Enables the synchronization overrun interrupt.
+    pub overrun_interrupt: bool,
+}
+
 /// DMAMUX channel driver.
 pub struct DmamuxCh<T: DmamuxChMap>(DmamuxChEn<T>);
 
@@ -66,4 +82,57 @@ impl<T: DmamuxChMap> DmamuxChEn<T> {
     pub fn set_dma_req_id(&self, id: u32) {
         self.periph.dmamux_ccr.dmareq_id().write_bits(id)
     }
+
+    /// Enables the synchronization of the DMA requests with `config`.
+    ///
+    /// The synchronization and the event generation are disabled while the
+    /// configuration is written, and enabled with a separate store.
+    ///
+    /// # Panics
+    ///
+    /// If `config.nbreq` is out of range.
+    pub fn enable_sync(&self, config: &DmamuxSyncConfig) {
+        if config.nbreq < 1 || config.nbreq > 32 {
+            panic!("DMAMUX channel nbreq out of range");
+        }
+        let ccr = &self.periph.dmamux_ccr;
+        let mut val = ccr.load_val();
+        ccr.se().clear(&mut val);
+        ccr.ege().clear(&mut val);
+        ccr.sync_id().write(&mut val, config.sync_id);
+        ccr.spol().write(&mut val, config.polarity.bits());
+        ccr.nbreq().write(&mut val, config.nbreq - 1);
+        if config.overrun_interrupt {
+            ccr.soie().set(&mut val);
+        } else {
+            ccr.soie().clear(&mut val);
+        }
+        ccr.store_val(val);
+        if config.event_generation {
+            ccr.ege().set(&mut val);
+        }
+        ccr.se().set(&mut val);
+        ccr.store_val(val);
+    }
+
+    /// Disables the synchronization of the DMA requests.
+    pub fn disable_sync(&self) {
+        let ccr = &self.periph.dmamux_ccr;
+        let mut val = ccr.load_val();
+        ccr.se().clear(&mut val);
+        ccr.ege().clear(&mut val);
+        ccr.soie().clear(&mut val);
+        ccr.store_val(val);
+    }
+
+    /// Returns `true` if a synchronization event occurred before the
+    /// previous requests were forwarded.
+    pub fn is_sync_overrun(&self) -> bool {
+        self.periph.dmamux_csr_sof.read_bit()
+    }
+
+    /// Clears the synchronization overrun flag.
+    pub fn clear_sync_overrun(&self) {
+        self.periph.dmamux_cfr_csof.set_bit();
+    }
 }
@@ -10,6 +10,19 @@ mod rg;
 
 pub use self::{ch::*, rg::*};
 
+/// DMAMUX trigger or synchronization input edges.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DmamuxPolarity {
+    /// No event, the input is ignored.
+    None,
+    /// Rising edge.
+    Rising,
+    /// Falling edge.
+    Falling,
+    /// Both rising and falling edges.
+    Both,
+}
+
 /// DMAMUX head driver.
 pub struct Dmamux<T: DmamuxMap>(Inventory0<DmamuxEn<T>>);
 
@@ -100,3 +113,14 @@ impl<T: DmamuxMap> DrvRcc for DmamuxEn<T> {
         self.periph.rcc_bussmenr_dmamuxsmen.set_bit();
     }
 }
+
+impl DmamuxPolarity {
+    fn bits(self) -> u32 {
+        match self {
+            Self::None => 0b00,
+            Self::Rising => 0b01,
+            Self::Falling => 0b10,
+            Self::Both => 0b11,
+        }
+    }
+}
@@ -1,6 +1,20 @@
-use super::DmamuxEn;
+use super::{DmamuxEn, DmamuxPolarity};
 use drone_core::inventory;
-use drone_stm32_map::periph::dma::mux::rg::{DmamuxRgMap, DmamuxRgPeriph};
+use drone_cortex_m::reg::prelude::*;
+use drone_stm32_map::periph::dma::mux::rg::{traits::*, DmamuxRgMap, DmamuxRgPeriph};
+
+/// DMAMUX request generator configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DmamuxRgConfig {
+    /// Trigger input signal ID.
+    pub signal_id: u32,
+    /// Trigger input edges.
+    pub polarity: DmamuxPolarity,
+    /// Number of DMA requests generated per trigger event, from 1 to 32.
+    pub nbreq: u32,
+    /// Enables the trigger overrun interrupt.
+    pub overrun_interrupt: bool,
+}
 
 /// DMAMUX request generator driver.
 pub struct DmamuxRg<T: DmamuxRgMap>(DmamuxRgEn<T>);
@@ -55,4 +69,42 @@ impl<T: DmamuxRgMap> DmamuxRgEn<T> {
         let token = unsafe { inventory::Token::new() };
         (DmamuxRg(self), token)
     }
+
+    /// Configures and enables the request generator.
+    ///
+    /// # Panics
+    ///
+    /// If `config.nbreq` is out of range.
+    pub fn enable(&self, config: &DmamuxRgConfig) {
+        if config.nbreq < 1 || config.nbreq > 32 {
+            panic!("DMAMUX request generator nbreq out of range");
+        }
+        let rgcr = &self.periph.dmamux_rgcr;
+        let mut val = rgcr.default_val();
+        rgcr.sig_id().write(&mut val, config.signal_id);
+        rgcr.gpol().write(&mut val, config.polarity.bits());
+        rgcr.gnbreq().write(&mut val, config.nbreq - 1);
+        if config.overrun_interrupt {
+            rgcr.oie().set(&mut val);
+        }
+        rgcr.store_val(val);
+        rgcr.ge().set(&mut val);
+        rgcr.store_val(val);
+    }
+
+    /// Disables the request generator.
+    pub fn disable(&self) {
+        self.periph.dmamux_rgcr.ge().clear_bit();
+    }
+
+    /// Returns `true` if a trigger event occurred before the previous
+    /// requests were served.
+    pub fn is_overrun(&self) -> bool {
+        self.periph.dmamux_rgsr_of.read_bit()
+    }
+
+    /// Clears the trigger overrun flag.
+    pub fn clear_overrun(&self) {
+        self.periph.dmamux_rgcfr_cof.set_bit();
+    }
 }
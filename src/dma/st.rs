@@ -0,0 +1,817 @@
+use super::{
+    DmaCircularError, DmaDir, DmaEn, DmaHalf, DmaPriority, DmaReadBuffer, DmaSize,
+    DmaTransferError, DmaWord, DmaWriteBuffer,
+};
+use core::{
+    pin::Pin,
+    ptr::read_volatile,
+    slice,
+    task::{Context, Poll},
+};
+use drone_core::{
+    inventory,
+    reg::{tag::Srt, Reg, RegField},
+};
+use drone_cortex_m::{fib, reg::prelude::*, thr::prelude::*};
+use drone_stm32_map::periph::dma::st::{traits::*, DmaStMap, DmaStPeriph};
+use futures::prelude::*;
+
+/// DMA stream FIFO configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DmaStFifo {
+    /// Direct mode, the FIFO is disabled.
+    Direct,
+    /// FIFO mode with 1/4 full threshold.
+    Quarter,
+    /// FIFO mode with 1/2 full threshold.
+    Half,
+    /// FIFO mode with 3/4 full threshold.
+    ThreeQuarters,
+    /// FIFO mode with full threshold.
+    Full,
+}
+
+/// DMA stream burst transfer configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DmaStBurst {
+    /// Single transfer.
+    Single,
+    /// Incremental burst of 4 beats.
+    Incr4,
+    /// Incremental burst of 8 beats.
+    Incr8,
+    /// Incremental burst of 16 beats.
+    Incr16,
+}
+
+/// DMA stream driver.
+pub struct DmaSt<T: DmaStMap, I: IntToken>(DmaStEn<T, I>);
+
+/// DMA stream enabled driver.
+pub struct DmaStEn<T: DmaStMap, I: IntToken> {
+    periph: DmaStDiverged<T>,
+    int: I,
+}
+
+/// DMA stream diverged peripheral.
+#[allow(missing_docs)]
+pub struct DmaStDiverged<T: DmaStMap> {
+    pub dma_scr: T::SDmaScr,
+    pub dma_sndtr: T::SDmaSndtr,
+    pub dma_spar: T::SDmaSpar,
+    pub dma_sm0ar: T::SDmaSm0Ar,
+    pub dma_sm1ar: T::SDmaSm1Ar,
+    pub dma_sfcr: T::SDmaSfcr,
+    pub dma_ifcr_cfeif: T::CDmaIfcrCfeif,
+    pub dma_ifcr_cdmeif: T::CDmaIfcrCdmeif,
+    pub dma_ifcr_cteif: T::CDmaIfcrCteif,
+    pub dma_ifcr_chtif: T::CDmaIfcrChtif,
+    pub dma_ifcr_ctcif: T::CDmaIfcrCtcif,
+    pub dma_isr_feif: T::CDmaIsrFeif,
+    pub dma_isr_dmeif: T::CDmaIsrDmeif,
+    pub dma_isr_teif: T::CDmaIsrTeif,
+    pub dma_isr_htif: T::CDmaIsrHtif,
+    pub dma_isr_tcif: T::CDmaIsrTcif,
+}
+
+/// DMA stream configuration builder.
+///
+/// Produces a `DMA_SxCR` value with the stream disabled. Every option is
+/// disabled by default, the direction is [`DmaDir::PeriphToMem`], the sizes
+/// are [`DmaSize::Bits8`], the bursts are [`DmaStBurst::Single`], the channel
+/// is `0`, and the priority is [`DmaPriority::Low`].
+pub struct DmaStConfig<'a, T: DmaStMap> {
+    cr: &'a T::SDmaScr,
+    val: T::DmaScrVal,
+}
+
+/// DMA stream transfer in progress.
+///
+/// The transfer owns the stream and the memory buffer, and gives them back on
+/// completion or cancellation. Dropping the transfer, or a future holding it,
+/// aborts the stream.
+#[must_use]
+pub struct DmaStTransfer<T: DmaStMap, I: IntToken, B> {
+    inner: Option<(DmaStEn<T, I>, B)>,
+    len: usize,
+    complete: Option<Pin<Box<dyn Future<Output = Result<(), DmaTransferError>> + Send>>>,
+}
+
+/// Continuous DMA stream transfer in the circular or the double-buffer mode.
+///
+/// The transfer is a stream of filled buffer halves, or filled buffers in the
+/// double-buffer mode, where [`DmaHalf::First`] is the memory 0 buffer.
+/// Dropping the transfer aborts the stream.
+#[must_use]
+pub struct DmaStCircular<T: DmaStMap, I: IntToken, B: DmaWriteBuffer> {
+    inner: Option<(DmaStEn<T, I>, B, Option<B>)>,
+    addrs: [usize; 2],
+    len: usize,
+    stream: Pin<Box<dyn Stream<Item = Result<DmaHalf, DmaCircularError>> + Send>>,
+}
+
+impl<T: DmaStMap, I: IntToken> DmaSt<T, I> {
+    /// Creates a new [`DmaSt`].
+    #[inline]
+    pub fn new(periph: DmaStPeriph<T>, int: I) -> Self {
+        let periph = DmaStDiverged {
+            dma_scr: periph.dma_scr,
+            dma_sndtr: periph.dma_sndtr,
+            dma_spar: periph.dma_spar,
+            dma_sm0ar: periph.dma_sm0ar,
+            dma_sm1ar: periph.dma_sm1ar,
+            dma_sfcr: periph.dma_sfcr,
+            dma_ifcr_cfeif: periph.dma_ifcr_cfeif.into_copy(),
+            dma_ifcr_cdmeif: periph.dma_ifcr_cdmeif.into_copy(),
+            dma_ifcr_cteif: periph.dma_ifcr_cteif.into_copy(),
+            dma_ifcr_chtif: periph.dma_ifcr_chtif.into_copy(),
+            dma_ifcr_ctcif: periph.dma_ifcr_ctcif.into_copy(),
+            dma_isr_feif: periph.dma_isr_feif.into_copy(),
+            dma_isr_dmeif: periph.dma_isr_dmeif.into_copy(),
+            dma_isr_teif: periph.dma_isr_teif.into_copy(),
+            dma_isr_htif: periph.dma_isr_htif.into_copy(),
+            dma_isr_tcif: periph.dma_isr_tcif.into_copy(),
+        };
+        Self(DmaStEn { periph, int })
+    }
+
+    /// Creates a new [`DmaSt`].
+    ///
+    /// # Safety
+    ///
+    /// Some of the `Crt` register tokens can be still in use.
+    #[inline]
+    pub unsafe fn from_diverged(periph: DmaStDiverged<T>, int: I) -> Self {
+        Self(DmaStEn { periph, int })
+    }
+
+    /// Releases the peripheral.
+    #[inline]
+    pub fn free(self) -> DmaStDiverged<T> {
+        self.0.periph
+    }
+
+    /// Acquires the enabled state.
+    #[inline]
+    pub fn as_enabled(&self, _token: &inventory::Token<DmaEn<T::DmaMap>>) -> &DmaStEn<T, I> {
+        &self.0
+    }
+
+    /// Acquires the enabled state.
+    #[inline]
+    pub fn as_enabled_mut(
+        &mut self,
+        _token: &inventory::Token<DmaEn<T::DmaMap>>,
+    ) -> &mut DmaStEn<T, I> {
+        &mut self.0
+    }
+
+    /// Acquires the enabled state.
+    #[inline]
+    pub fn into_enabled(self, token: inventory::Token<DmaEn<T::DmaMap>>) -> DmaStEn<T, I> {
+        // To be recreated in `into_disabled()`.
+        drop(token);
+        self.0
+    }
+}
+
+impl<T: DmaStMap, I: IntToken> DmaStEn<T, I> {
+    /// Releases the enabled state.
+    #[inline]
+    pub fn into_disabled(self) -> (DmaSt<T, I>, inventory::Token<DmaEn<T::DmaMap>>) {
+        // Restoring the token dropped in `into_enabled()`.
+        let token = unsafe { inventory::Token::new() };
+        (DmaSt(self), token)
+    }
+
+    /// Returns a number of data to transfer.
+    pub fn size(&self) -> usize {
+        self.periph.dma_sndtr.ndt().read_bits() as usize
+    }
+
+    /// Sets the number of data to transfer.
+    pub fn set_size(&self, number: usize) {
+        self.periph.dma_sndtr.ndt().write_bits(number as u32);
+    }
+
+    /// Returns the peripheral address.
+    pub fn paddr<P>(&self) -> *mut P {
+        self.periph.dma_spar.pa().read_bits() as _
+    }
+
+    /// Sets the peripheral address.
+    ///
+    /// # Safety
+    ///
+    /// The method works with raw pointer.
+    pub unsafe fn set_paddr<P>(&self, addr: *const P) {
+        self.periph.dma_spar.pa().write_bits(addr as _);
+    }
+
+    /// Returns the memory 0 address.
+    pub fn maddr<M>(&self) -> *mut M {
+        self.periph.dma_sm0ar.m0a().read_bits() as _
+    }
+
+    /// Sets the memory 0 address.
+    ///
+    /// # Safety
+    ///
+    /// The method works with raw pointer.
+    pub unsafe fn set_maddr<M>(&self, addr: *const M) {
+        self.periph.dma_sm0ar.m0a().write_bits(addr as _);
+    }
+
+    /// Returns the memory 1 address.
+    pub fn m1addr<M>(&self) -> *mut M {
+        self.periph.dma_sm1ar.m1a().read_bits() as _
+    }
+
+    /// Sets the memory 1 address, which is used in the double-buffer mode.
+    ///
+    /// # Safety
+    ///
+    /// The method works with raw pointer.
+    pub unsafe fn set_m1addr<M>(&self, addr: *const M) {
+        self.periph.dma_sm1ar.m1a().write_bits(addr as _);
+    }
+
+    /// Configures the FIFO. The stream must be disabled.
+    pub fn set_fifo(&self, fifo: DmaStFifo) {
+        let fcr = &self.periph.dma_sfcr;
+        fcr.store_val({
+            let mut val = fcr.default_val();
+            match fifo {
+                DmaStFifo::Direct => {
+                    fcr.dmdis().clear(&mut val);
+                }
+                DmaStFifo::Quarter => {
+                    fcr.dmdis().set(&mut val);
+                    fcr.fth().write(&mut val, 0b00);
+                }
+                DmaStFifo::Half => {
+                    fcr.dmdis().set(&mut val);
+                    fcr.fth().write(&mut val, 0b01);
+                }
+                DmaStFifo::ThreeQuarters => {
+                    fcr.dmdis().set(&mut val);
+                    fcr.fth().write(&mut val, 0b10);
+                }
+                DmaStFifo::Full => {
+                    fcr.dmdis().set(&mut val);
+                    fcr.fth().write(&mut val, 0b11);
+                }
+            }
+            val
+        });
+    }
+
+    /// Returns a new stream configuration builder.
+    pub fn config(&self) -> DmaStConfig<'_, T> {
+        let cr = &self.periph.dma_scr;
+        DmaStConfig {
+            cr,
+            val: cr.default_val(),
+        }
+    }
+
+    /// Returns a future, which resolves on DMA transfer complete event.
+    pub fn transfer_complete(&self) -> impl Future<Output = Result<(), DmaTransferError>> {
+        let teif = self.periph.dma_isr_teif;
+        let dmeif = self.periph.dma_isr_dmeif;
+        let tcif = self.periph.dma_isr_tcif;
+        let cteif = self.periph.dma_ifcr_cteif;
+        let cdmeif = self.periph.dma_ifcr_cdmeif;
+        let ctcif = self.periph.dma_ifcr_ctcif;
+        self.int.add_future(fib::new_fn(move || {
+            if teif.read_bit() || dmeif.read_bit() {
+                cteif.set_bit();
+                cdmeif.set_bit();
+                fib::Complete(Err(DmaTransferError))
+            } else if tcif.read_bit() {
+                ctcif.set_bit();
+                fib::Complete(Ok(()))
+            } else {
+                fib::Yielded(())
+            }
+        }))
+    }
+
+    /// Returns a future, which resolves on DMA half transfer event.
+    pub fn half_transfer(&self) -> impl Future<Output = Result<(), DmaTransferError>> {
+        let teif = self.periph.dma_isr_teif;
+        let dmeif = self.periph.dma_isr_dmeif;
+        let htif = self.periph.dma_isr_htif;
+        let cteif = self.periph.dma_ifcr_cteif;
+        let cdmeif = self.periph.dma_ifcr_cdmeif;
+        let chtif = self.periph.dma_ifcr_chtif;
+        self.int.add_future(fib::new_fn(move || {
+            if teif.read_bit() || dmeif.read_bit() {
+                cteif.set_bit();
+                cdmeif.set_bit();
+                fib::Complete(Err(DmaTransferError))
+            } else if htif.read_bit() {
+                chtif.set_bit();
+                fib::Complete(Ok(()))
+            } else {
+                fib::Yielded(())
+            }
+        }))
+    }
+
+    /// Starts a peripheral-to-memory transfer into `buf`.
+    ///
+    /// The stream is configured with `cr_val`, where the memory size and the
+    /// direction fields are overridden according to `buf`. The peripheral
+    /// address should be already initialized.
+    ///
+    /// # Panics
+    ///
+    /// If length of `buf` is greater than 65535.
+    pub fn start_rx<B: DmaWriteBuffer>(
+        self,
+        mut buf: B,
+        mut cr_val: T::DmaScrVal,
+    ) -> DmaStTransfer<T, I, B> {
+        let (addr, len) = buf.dma_write_buffer();
+        let complete = Box::pin(self.transfer_complete());
+        self.periph
+            .dma_scr
+            .dir()
+            .write(&mut cr_val, dir_bits(DmaDir::PeriphToMem));
+        self.start(addr as *const B::Word, len, cr_val);
+        DmaStTransfer {
+            inner: Some((self, buf)),
+            len,
+            complete: Some(complete),
+        }
+    }
+
+    /// Starts a memory-to-peripheral transfer from `buf`.
+    ///
+    /// The stream is configured with `cr_val`, where the memory size and the
+    /// direction fields are overridden according to `buf`. The peripheral
+    /// address should be already initialized.
+    ///
+    /// # Panics
+    ///
+    /// If length of `buf` is greater than 65535.
+    pub fn start_tx<B: DmaReadBuffer>(
+        self,
+        buf: B,
+        mut cr_val: T::DmaScrVal,
+    ) -> DmaStTransfer<T, I, B> {
+        let (addr, len) = buf.dma_read_buffer();
+        let complete = Box::pin(self.transfer_complete());
+        self.periph
+            .dma_scr
+            .dir()
+            .write(&mut cr_val, dir_bits(DmaDir::MemToPeriph));
+        self.start(addr, len, cr_val);
+        DmaStTransfer {
+            inner: Some((self, buf)),
+            len,
+            complete: Some(complete),
+        }
+    }
+
+    /// Starts a circular peripheral-to-memory transfer into `buf`.
+    ///
+    /// The stream is configured with `cr_val`, where the circular mode, the
+    /// interrupts, the memory size and the direction fields are overridden.
+    /// The peripheral address should be already initialized. `capacity` is the
+    /// number of filled halves, which can be buffered before
+    /// [`DmaCircularError::Overrun`] is reported.
+    ///
+    /// # Panics
+    ///
+    /// If length of `buf` is odd or greater than 65535.
+    pub fn start_circular_rx<B: DmaWriteBuffer>(
+        self,
+        mut buf: B,
+        mut cr_val: T::DmaScrVal,
+        capacity: usize,
+    ) -> DmaStCircular<T, I, B> {
+        let (addr, len) = buf.dma_write_buffer();
+        if len % 2 != 0 {
+            panic!("DMA circular buffer length is odd");
+        }
+        let stream = Box::pin(self.circular_stream(capacity, false));
+        let cr = &self.periph.dma_scr;
+        cr.dir().write(&mut cr_val, dir_bits(DmaDir::PeriphToMem));
+        cr.circ().set(&mut cr_val);
+        cr.teie().set(&mut cr_val);
+        cr.dmeie().set(&mut cr_val);
+        cr.htie().set(&mut cr_val);
+        cr.tcie().set(&mut cr_val);
+        self.start(addr as *const B::Word, len, cr_val);
+        DmaStCircular {
+            inner: Some((self, buf, None)),
+            addrs: [
+                addr as usize,
+                addr as usize + len / 2 * core::mem::size_of::<B::Word>(),
+            ],
+            len: len / 2,
+            stream,
+        }
+    }
+
+    /// Starts a double-buffer peripheral-to-memory transfer, alternating
+    /// between `buf0` and `buf1`.
+    ///
+    /// The stream is configured with `cr_val`, where the double-buffer mode,
+    /// the interrupts, the memory size and the direction fields are
+    /// overridden. The peripheral address should be already initialized.
+    /// `capacity` is the number of filled buffers, which can be buffered before
+    /// [`DmaCircularError::Overrun`] is reported.
+    ///
+    /// # Panics
+    ///
+    /// If lengths of `buf0` and `buf1` differ, or greater than 65535.
+    pub fn start_double_buffer_rx<B: DmaWriteBuffer>(
+        self,
+        mut buf0: B,
+        mut buf1: B,
+        mut cr_val: T::DmaScrVal,
+        capacity: usize,
+    ) -> DmaStCircular<T, I, B> {
+        let (addr0, len0) = buf0.dma_write_buffer();
+        let (addr1, len1) = buf1.dma_write_buffer();
+        if len0 != len1 {
+            panic!("DMA double-buffer length mismatch");
+        }
+        let stream = Box::pin(self.circular_stream(capacity, true));
+        let cr = &self.periph.dma_scr;
+        cr.dir().write(&mut cr_val, dir_bits(DmaDir::PeriphToMem));
+        cr.dbm().set(&mut cr_val);
+        cr.ct().clear(&mut cr_val);
+        cr.teie().set(&mut cr_val);
+        cr.dmeie().set(&mut cr_val);
+        cr.htie().clear(&mut cr_val);
+        cr.tcie().set(&mut cr_val);
+        self.abort();
+        unsafe { self.set_m1addr(addr1) };
+        self.start(addr0 as *const B::Word, len0, cr_val);
+        DmaStCircular {
+            inner: Some((self, buf0, Some(buf1))),
+            addrs: [addr0 as usize, addr1 as usize],
+            len: len0,
+            stream,
+        }
+    }
+
+    fn start<W: DmaWord>(&self, addr: *const W, len: usize, mut cr_val: T::DmaScrVal) {
+        if len > usize::from(u16::MAX) {
+            panic!("DMA transfer length overflow");
+        }
+        let cr = &self.periph.dma_scr;
+        cr.en().clear_bit();
+        while cr.en().read_bit() {}
+        self.clear_flags();
+        unsafe { self.set_maddr(addr) };
+        self.set_size(len);
+        cr.msize().write(&mut cr_val, W::SIZE.bits());
+        cr.en().set(&mut cr_val);
+        cr.store_val(cr_val);
+    }
+
+    /// Disables the stream and clears its interrupt flags. Returns the number
+    /// of data left to transfer.
+    pub fn abort(&self) -> usize {
+        self.periph.dma_scr.en().clear_bit();
+        while self.periph.dma_scr.en().read_bit() {}
+        self.clear_flags();
+        self.size()
+    }
+
+    fn clear_flags(&self) {
+        self.periph.dma_ifcr_cfeif.set_bit();
+        self.periph.dma_ifcr_cdmeif.set_bit();
+        self.periph.dma_ifcr_cteif.set_bit();
+        self.periph.dma_ifcr_chtif.set_bit();
+        self.periph.dma_ifcr_ctcif.set_bit();
+    }
+
+    fn circular_stream(
+        &self,
+        capacity: usize,
+        double_buffer: bool,
+    ) -> impl Stream<Item = Result<DmaHalf, DmaCircularError>> + Send {
+        let teif = self.periph.dma_isr_teif;
+        let dmeif = self.periph.dma_isr_dmeif;
+        let htif = self.periph.dma_isr_htif;
+        let tcif = self.periph.dma_isr_tcif;
+        let cteif = self.periph.dma_ifcr_cteif;
+        let cdmeif = self.periph.dma_ifcr_cdmeif;
+        let chtif = self.periph.dma_ifcr_chtif;
+        let ctcif = self.periph.dma_ifcr_ctcif;
+        let (cr_addr, ct_offset) = field_location(self.periph.dma_scr.ct());
+        self.int.add_stream_ring(
+            capacity,
+            |_| Err(DmaCircularError::Overrun),
+            fib::new_fn(move || {
+                if teif.read_bit() || dmeif.read_bit() {
+                    cteif.set_bit();
+                    cdmeif.set_bit();
+                    fib::Complete(Err(DmaCircularError::Transfer(DmaTransferError)))
+                } else if htif.read_bit() {
+                    chtif.set_bit();
+                    fib::Yielded(Some(DmaHalf::First))
+                } else if tcif.read_bit() {
+                    ctcif.set_bit();
+                    if double_buffer {
+                        // CT already points to the buffer being filled now,
+                        // so the filled one is the other.
+                        let ct = unsafe { read_volatile(cr_addr as *const u32) } & 1 << ct_offset;
+                        if ct == 0 {
+                            fib::Yielded(Some(DmaHalf::Second))
+                        } else {
+                            fib::Yielded(Some(DmaHalf::First))
+                        }
+                    } else {
+                        fib::Yielded(Some(DmaHalf::Second))
+                    }
+                } else {
+                    fib::Yielded(None)
+                }
+            }),
+        )
+    }
+}
+
+#[allow(missing_docs)]
+impl<T: DmaStMap, I: IntToken> DmaStEn<T, I> {
+    #[inline]
+    pub fn int(&self) -> &I {
+        &self.int
+    }
+
+    #[inline]
+    pub fn cr(&self) -> &T::SDmaScr {
+        &self.periph.dma_scr
+    }
+
+    #[inline]
+    pub fn fcr(&self) -> &T::SDmaSfcr {
+        &self.periph.dma_sfcr
+    }
+}
+
+impl<'a, T: DmaStMap> DmaStConfig<'a, T> {
+    /// Sets the transfer direction.
+    pub fn dir(mut self, dir: DmaDir) -> Self {
+        self.cr.dir().write(&mut self.val, dir_bits(dir));
+        self
+    }
+
+    /// Selects the channel, which is the request source of the stream.
+    pub fn ch(mut self, ch: u32) -> Self {
+        self.cr.chsel().write(&mut self.val, ch);
+        self
+    }
+
+    /// Sets the memory data element size.
+    pub fn msize(mut self, size: DmaSize) -> Self {
+        self.cr.msize().write(&mut self.val, size.bits());
+        self
+    }
+
+    /// Sets the peripheral data element size.
+    pub fn psize(mut self, size: DmaSize) -> Self {
+        self.cr.psize().write(&mut self.val, size.bits());
+        self
+    }
+
+    /// Enables or disables the memory address increment.
+    pub fn minc(mut self, enable: bool) -> Self {
+        if enable {
+            self.cr.minc().set(&mut self.val);
+        } else {
+            self.cr.minc().clear(&mut self.val);
+        }
+        self
+    }
+
+    /// Enables or disables the peripheral address increment.
+    pub fn pinc(mut self, enable: bool) -> Self {
+        if enable {
+            self.cr.pinc().set(&mut self.val);
+        } else {
+            self.cr.pinc().clear(&mut self.val);
+        }
+        self
+    }
+
+    /// Sets the memory burst transfer configuration.
+    pub fn mburst(mut self, burst: DmaStBurst) -> Self {
+        self.cr.mburst().write(&mut self.val, burst.bits());
+        self
+    }
+
+    /// Sets the peripheral burst transfer configuration.
+    pub fn pburst(mut self, burst: DmaStBurst) -> Self {
+        self.cr.pburst().write(&mut self.val, burst.bits());
+        self
+    }
+
+    /// Sets the stream priority level.
+    pub fn priority(mut self, priority: DmaPriority) -> Self {
+        self.cr.pl().write(&mut self.val, match priority {
+            DmaPriority::Low => 0b00,
+            DmaPriority::Medium => 0b01,
+            DmaPriority::High => 0b10,
+            DmaPriority::VeryHigh => 0b11,
+        });
+        self
+    }
+
+    /// Enables or disables the circular mode.
+    pub fn circ(mut self, enable: bool) -> Self {
+        if enable {
+            self.cr.circ().set(&mut self.val);
+        } else {
+            self.cr.circ().clear(&mut self.val);
+        }
+        self
+    }
+
+    /// Enables or disables the transfer error and the direct mode error
+    /// interrupts.
+    pub fn teie(mut self, enable: bool) -> Self {
+        if enable {
+            self.cr.teie().set(&mut self.val);
+            self.cr.dmeie().set(&mut self.val);
+        } else {
+            self.cr.teie().clear(&mut self.val);
+            self.cr.dmeie().clear(&mut self.val);
+        }
+        self
+    }
+
+    /// Enables or disables the half transfer interrupt.
+    pub fn htie(mut self, enable: bool) -> Self {
+        if enable {
+            self.cr.htie().set(&mut self.val);
+        } else {
+            self.cr.htie().clear(&mut self.val);
+        }
+        self
+    }
+
+    /// Enables or disables the transfer complete interrupt.
+    pub fn tcie(mut self, enable: bool) -> Self {
+        if enable {
+            self.cr.tcie().set(&mut self.val);
+        } else {
+            self.cr.tcie().clear(&mut self.val);
+        }
+        self
+    }
+
+    /// Returns the `DMA_SxCR` value.
+    pub fn build(self) -> T::DmaScrVal {
+        self.val
+    }
+}
+
+impl<T: DmaStMap, I: IntToken, B> DmaStTransfer<T, I, B> {
+    /// Returns a reference to the stream.
+    #[inline]
+    pub fn st(&self) -> &DmaStEn<T, I> {
+        &self.inner.as_ref().unwrap().0
+    }
+
+    /// Returns a future, which resolves on the transfer completion.
+    ///
+    /// The future is attached before the stream is enabled, so an early
+    /// completion isn't missed.
+    ///
+    /// # Panics
+    ///
+    /// If the future was already taken by this method or by
+    /// [`wait`](DmaStTransfer::wait).
+    pub fn transfer_complete(
+        &mut self,
+    ) -> impl Future<Output = Result<(), DmaTransferError>> + Send + Unpin {
+        match self.complete.take() {
+            Some(complete) => complete,
+            None => panic!("DMA transfer future was already taken"),
+        }
+    }
+
+    /// Returns a future, which resolves on the transfer completion, giving
+    /// back the stream and the buffer.
+    ///
+    /// # Panics
+    ///
+    /// If the future was already taken by
+    /// [`transfer_complete`](DmaStTransfer::transfer_complete).
+    pub fn wait(
+        mut self,
+    ) -> impl Future<Output = (DmaStEn<T, I>, B, Result<(), DmaTransferError>)> {
+        let transfer_complete = self.transfer_complete();
+        async move {
+            let result = transfer_complete.await;
+            let (st, buf) = self.stop();
+            (st, buf, result)
+        }
+    }
+
+    /// Returns the number of data transferred so far.
+    pub fn transferred(&self) -> usize {
+        self.len - self.st().size()
+    }
+
+    /// Stops the stream, giving back the stream and the buffer.
+    pub fn stop(self) -> (DmaStEn<T, I>, B) {
+        let (st, buf, _) = self.abort();
+        (st, buf)
+    }
+
+    /// Aborts the transfer, giving back the stream, the buffer, and the number
+    /// of data actually transferred.
+    pub fn abort(mut self) -> (DmaStEn<T, I>, B, usize) {
+        let (st, buf) = self.inner.take().unwrap();
+        let transferred = self.len - st.abort();
+        (st, buf, transferred)
+    }
+}
+
+impl<T: DmaStMap, I: IntToken, B> Drop for DmaStTransfer<T, I, B> {
+    fn drop(&mut self) {
+        if let Some((st, _)) = &self.inner {
+            st.abort();
+        }
+    }
+}
+
+impl<T: DmaStMap, I: IntToken, B: DmaWriteBuffer> DmaStCircular<T, I, B> {
+    /// Returns a reference to the stream.
+    #[inline]
+    pub fn st(&self) -> &DmaStEn<T, I> {
+        &self.inner.as_ref().unwrap().0
+    }
+
+    /// Returns the content of the buffer `half`.
+    ///
+    /// The half is overwritten when the stream reaches it again, therefore it
+    /// should be processed before the next item of the stream.
+    pub fn half(&self, half: DmaHalf) -> &[B::Word] {
+        let addr = match half {
+            DmaHalf::First => self.addrs[0],
+            DmaHalf::Second => self.addrs[1],
+        };
+        unsafe { slice::from_raw_parts(addr as *const B::Word, self.len) }
+    }
+
+    /// Stops the stream, giving back the stream and the buffers. The second
+    /// buffer is present only in the double-buffer mode.
+    pub fn stop(mut self) -> (DmaStEn<T, I>, B, Option<B>) {
+        let (st, buf0, buf1) = self.inner.take().unwrap();
+        st.abort();
+        (st, buf0, buf1)
+    }
+}
+
+impl<T: DmaStMap, I: IntToken, B: DmaWriteBuffer> Stream for DmaStCircular<T, I, B> {
+    type Item = Result<DmaHalf, DmaCircularError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().stream.as_mut().poll_next(cx)
+    }
+}
+
+impl<T: DmaStMap, I: IntToken, B: DmaWriteBuffer> Unpin for DmaStCircular<T, I, B> {}
+
+impl<T: DmaStMap, I: IntToken, B: DmaWriteBuffer> Drop for DmaStCircular<T, I, B> {
+    fn drop(&mut self) {
+        if let Some((st, _, _)) = &self.inner {
+            st.abort();
+        }
+    }
+}
+
+impl DmaStBurst {
+    fn bits(self) -> u32 {
+        match self {
+            Self::Single => 0b00,
+            Self::Incr4 => 0b01,
+            Self::Incr8 => 0b10,
+            Self::Incr16 => 0b11,
+        }
+    }
+}
+
+/// Returns the register address and the bit offset of `field`, which can be
+/// used from a fiber without owning the register token.
+fn field_location<F: RegField<Srt>>(_field: &F) -> (usize, usize) {
+    (F::Reg::ADDRESS, F::OFFSET)
+}
+
+fn dir_bits(dir: DmaDir) -> u32 {
+    match dir {
+        DmaDir::PeriphToMem => 0b00,
+        DmaDir::MemToPeriph => 0b01,
+        DmaDir::MemToMem => 0b10,
+    }
+}
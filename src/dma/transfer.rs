@@ -0,0 +1,228 @@
+use super::{DmaChEn, DmaDir, DmaReadBuffer, DmaTransferError, DmaWord, DmaWriteBuffer};
+use core::pin::Pin;
+use drone_cortex_m::{reg::prelude::*, thr::prelude::*};
+use drone_stm32_map::periph::dma::ch::{traits::*, DmaChMap};
+use futures::prelude::*;
+
+/// DMA transfer in progress.
+///
+/// The transfer owns the channel and the memory buffer, and gives them back on
+/// completion or cancellation. Dropping the transfer, or a future holding it,
+/// aborts the channel.
+#[must_use]
+pub struct DmaTransfer<T: DmaChMap, I: IntToken, B> {
+    inner: Option<(DmaChEn<T, I>, B)>,
+    len: usize,
+    complete: Option<Pin<Box<dyn Future<Output = Result<(), DmaTransferError>> + Send>>>,
+}
+
+impl<T: DmaChMap, I: IntToken> DmaChEn<T, I> {
+    /// Starts a peripheral-to-memory transfer into `buf`.
+    ///
+    /// The channel is configured with `ccr_val`, where the memory size and the
+    /// direction fields are overridden according to `buf`. The peripheral
+    /// address should be already initialized.
+    ///
+    /// # Panics
+    ///
+    /// If length of `buf` is greater than 65535.
+    pub fn start_rx<B: DmaWriteBuffer>(
+        self,
+        mut buf: B,
+        mut ccr_val: T::DmaCcrVal,
+    ) -> DmaTransfer<T, I, B> {
+        let (addr, len) = buf.dma_write_buffer();
+        let complete = Box::pin(self.transfer_complete());
+        self.ccr().dir().clear(&mut ccr_val);
+        self.start(addr as *const B::Word, len, ccr_val);
+        DmaTransfer {
+            inner: Some((self, buf)),
+            len,
+            complete: Some(complete),
+        }
+    }
+
+    /// Starts a memory-to-peripheral transfer from `buf`.
+    ///
+    /// The channel is configured with `ccr_val`, where the memory size and the
+    /// direction fields are overridden according to `buf`. The peripheral
+    /// address should be already initialized.
+    ///
+    /// # Panics
+    ///
+    /// If length of `buf` is greater than 65535.
+    pub fn start_tx<B: DmaReadBuffer>(
+        self,
+        buf: B,
+        mut ccr_val: T::DmaCcrVal,
+    ) -> DmaTransfer<T, I, B> {
+        let (addr, len) = buf.dma_read_buffer();
+        let complete = Box::pin(self.transfer_complete());
+        self.ccr().dir().set(&mut ccr_val);
+        self.start(addr, len, ccr_val);
+        DmaTransfer {
+            inner: Some((self, buf)),
+            len,
+            complete: Some(complete),
+        }
+    }
+
+    /// Starts a memory-to-memory transfer copying `src` to `dst`.
+    ///
+    /// The returned future gives back the channel and the buffers.
+    ///
+    /// # Panics
+    ///
+    /// If lengths of `src` and `dst` differ, or greater than 65535.
+    pub fn copy<S: DmaReadBuffer, D: DmaWriteBuffer<Word = S::Word>>(
+        self,
+        src: S,
+        mut dst: D,
+    ) -> impl Future<Output = (Self, S, D, Result<(), DmaTransferError>)> {
+        let (src_addr, src_len) = src.dma_read_buffer();
+        let (dst_addr, dst_len) = dst.dma_write_buffer();
+        if src_len != dst_len {
+            panic!("DMA copy length mismatch");
+        }
+        let complete = Box::pin(self.transfer_complete());
+        self.start_mem2mem(src_addr, dst_addr, dst_len, true);
+        let transfer = DmaTransfer {
+            inner: Some((self, (src, dst))),
+            len: dst_len,
+            complete: Some(complete),
+        };
+        async move {
+            let (ch, (src, dst), result) = transfer.wait().await;
+            (ch, src, dst, result)
+        }
+    }
+
+    /// Starts a memory-to-memory transfer filling `dst` with `value`.
+    ///
+    /// The returned future gives back the channel and the buffer.
+    ///
+    /// # Panics
+    ///
+    /// If length of `dst` is greater than 65535.
+    pub fn fill<D: DmaWriteBuffer>(
+        self,
+        value: D::Word,
+        mut dst: D,
+    ) -> impl Future<Output = (Self, D, Result<(), DmaTransferError>)> {
+        let value = Box::new(value);
+        let (dst_addr, dst_len) = dst.dma_write_buffer();
+        let complete = Box::pin(self.transfer_complete());
+        self.start_mem2mem(&*value, dst_addr, dst_len, false);
+        let transfer = DmaTransfer {
+            inner: Some((self, (value, dst))),
+            len: dst_len,
+            complete: Some(complete),
+        };
+        async move {
+            let (ch, (_, dst), result) = transfer.wait().await;
+            (ch, dst, result)
+        }
+    }
+
+    pub(super) fn start<W: DmaWord>(&self, addr: *const W, len: usize, mut ccr_val: T::DmaCcrVal) {
+        if len > usize::from(u16::MAX) {
+            panic!("DMA transfer length overflow");
+        }
+        self.abort();
+        unsafe { self.set_maddr(addr) };
+        self.set_size(len);
+        self.ccr().msize().write(&mut ccr_val, W::SIZE.bits());
+        self.ccr().en().set(&mut ccr_val);
+        self.ccr().store_val(ccr_val);
+    }
+
+    fn start_mem2mem<W: DmaWord>(&self, src: *const W, dst: *mut W, len: usize, src_inc: bool) {
+        self.ccr().en().clear_bit();
+        unsafe { self.set_paddr(dst) };
+        let ccr_val = self
+            .config()
+            .dir(DmaDir::MemToMem)
+            .psize(W::SIZE)
+            .minc(src_inc)
+            .pinc(true)
+            .teie(true)
+            .tcie(true)
+            .build();
+        self.start(src, len, ccr_val);
+    }
+}
+
+impl<T: DmaChMap, I: IntToken, B> DmaTransfer<T, I, B> {
+    /// Returns a reference to the channel.
+    #[inline]
+    pub fn ch(&self) -> &DmaChEn<T, I> {
+        &self.parts().0
+    }
+
+    /// Returns a future, which resolves on the transfer completion.
+    ///
+    /// The future is attached before the channel is enabled, so an early
+    /// completion isn't missed.
+    ///
+    /// # Panics
+    ///
+    /// If the future was already taken by this method or by
+    /// [`wait`](DmaTransfer::wait).
+    pub fn transfer_complete(
+        &mut self,
+    ) -> impl Future<Output = Result<(), DmaTransferError>> + Send + Unpin {
+        match self.complete.take() {
+            Some(complete) => complete,
+            None => panic!("DMA transfer future was already taken"),
+        }
+    }
+
+    /// Returns a future, which resolves on the transfer completion, giving
+    /// back the channel and the buffer.
+    ///
+    /// # Panics
+    ///
+    /// If the future was already taken by
+    /// [`transfer_complete`](DmaTransfer::transfer_complete).
+    pub fn wait(
+        mut self,
+    ) -> impl Future<Output = (DmaChEn<T, I>, B, Result<(), DmaTransferError>)> {
+        let transfer_complete = self.transfer_complete();
+        async move {
+            let result = transfer_complete.await;
+            let (ch, buf) = self.stop();
+            (ch, buf, result)
+        }
+    }
+
+    /// Returns the number of data transferred so far.
+    pub fn transferred(&self) -> usize {
+        self.len - self.ch().size()
+    }
+
+    /// Stops the channel, giving back the channel and the buffer.
+    pub fn stop(self) -> (DmaChEn<T, I>, B) {
+        let (ch, buf, _) = self.abort();
+        (ch, buf)
+    }
+
+    /// Aborts the transfer, giving back the channel, the buffer, and the
+    /// number of data actually transferred.
+    pub fn abort(mut self) -> (DmaChEn<T, I>, B, usize) {
+        let (ch, buf) = self.inner.take().unwrap();
+        let transferred = self.len - ch.abort();
+        (ch, buf, transferred)
+    }
+
+    fn parts(&self) -> &(DmaChEn<T, I>, B) {
+        self.inner.as_ref().unwrap()
+    }
+}
+
+impl<T: DmaChMap, I: IntToken, B> Drop for DmaTransfer<T, I, B> {
+    fn drop(&mut self) {
+        if let Some((ch, _)) = &self.inner {
+            ch.abort();
+        }
+    }
+}
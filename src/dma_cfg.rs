@@ -0,0 +1,45 @@
+/// Keeps `$item`s only on MCUs with the channel-based DMA controller.
+#[allow(unused_macros)]
+macro_rules! dma_ch {
+    ($($item:item)*) => {
+        $(
+            #[cfg(not(any(
+                stm32_mcu = "stm32f401",
+                stm32_mcu = "stm32f405",
+                stm32_mcu = "stm32f407",
+                stm32_mcu = "stm32f410",
+                stm32_mcu = "stm32f411",
+                stm32_mcu = "stm32f412",
+                stm32_mcu = "stm32f413",
+                stm32_mcu = "stm32f427",
+                stm32_mcu = "stm32f429",
+                stm32_mcu = "stm32f446",
+                stm32_mcu = "stm32f469"
+            )))]
+            $item
+        )*
+    };
+}
+
+/// Keeps `$item`s only on MCUs with the stream-based DMA controller.
+#[allow(unused_macros)]
+macro_rules! dma_st {
+    ($($item:item)*) => {
+        $(
+            #[cfg(any(
+                stm32_mcu = "stm32f401",
+                stm32_mcu = "stm32f405",
+                stm32_mcu = "stm32f407",
+                stm32_mcu = "stm32f410",
+                stm32_mcu = "stm32f411",
+                stm32_mcu = "stm32f412",
+                stm32_mcu = "stm32f413",
+                stm32_mcu = "stm32f427",
+                stm32_mcu = "stm32f429",
+                stm32_mcu = "stm32f446",
+                stm32_mcu = "stm32f469"
+            ))]
+            $item
+        )*
+    };
+}
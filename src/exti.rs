@@ -0,0 +1,245 @@
+//! Extended interrupts and events controller.
+
+use core::{fmt, ptr::write_volatile};
+use drone_core::reg::{tag::Crt, Reg, RegField};
+use drone_cortex_m::{
+    fib::{self, Fiber},
+    reg::prelude::*,
+    thr::prelude::*,
+};
+use drone_stm32_map::periph::exti::{ExtiMap, ExtiPeriph};
+use futures::prelude::*;
+
+/// EXTI event stream overflow.
+#[derive(Debug)]
+pub struct ExtiOverflow;
+
+/// EXTI line trigger selection.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExtiTrigger {
+    /// Rising edge trigger.
+    Rising,
+    /// Falling edge trigger.
+    Falling,
+    /// Both rising and falling edges trigger.
+    Any,
+}
+
+/// EXTI line driver.
+pub struct Exti<T: ExtiMap, I: IntToken> {
+    periph: ExtiDiverged<T>,
+    int: I,
+}
+
+/// EXTI line diverged peripheral.
+#[allow(missing_docs)]
+pub struct ExtiDiverged<T: ExtiMap> {
+    pub exti_imr_im: T::SExtiImrIm,
+    pub exti_emr_em: T::SExtiEmrEm,
+    pub exti_rtsr_rt: T::SExtiRtsrRt,
+    pub exti_ftsr_ft: T::SExtiFtsrFt,
+    pub exti_swier_swi: T::SExtiSwierSwi,
+    pub exti_pr_pif: T::CExtiPrPif,
+    #[cfg(any(
+        stm32_mcu = "stm32f100",
+        stm32_mcu = "stm32f101",
+        stm32_mcu = "stm32f102",
+        stm32_mcu = "stm32f103",
+        stm32_mcu = "stm32f107"
+    ))]
+    pub afio_exticr_exti: T::SAfioExticrExti,
+    #[cfg(not(any(
+        stm32_mcu = "stm32f100",
+        stm32_mcu = "stm32f101",
+        stm32_mcu = "stm32f102",
+        stm32_mcu = "stm32f103",
+        stm32_mcu = "stm32f107"
+    )))]
+    pub syscfg_exticr_exti: T::SSyscfgExticrExti,
+}
+
+impl<T: ExtiMap, I: IntToken> Exti<T, I> {
+    /// Creates a new [`Exti`].
+    #[inline]
+    pub fn new(periph: ExtiPeriph<T>, int: I) -> Self {
+        let periph = ExtiDiverged {
+            exti_imr_im: periph.exti_imr_im,
+            exti_emr_em: periph.exti_emr_em,
+            exti_rtsr_rt: periph.exti_rtsr_rt,
+            exti_ftsr_ft: periph.exti_ftsr_ft,
+            exti_swier_swi: periph.exti_swier_swi,
+            exti_pr_pif: periph.exti_pr_pif.into_copy(),
+            #[cfg(any(
+                stm32_mcu = "stm32f100",
+                stm32_mcu = "stm32f101",
+                stm32_mcu = "stm32f102",
+                stm32_mcu = "stm32f103",
+                stm32_mcu = "stm32f107"
+            ))]
+            afio_exticr_exti: periph.afio_exticr_exti,
+            #[cfg(not(any(
+                stm32_mcu = "stm32f100",
+                stm32_mcu = "stm32f101",
+                stm32_mcu = "stm32f102",
+                stm32_mcu = "stm32f103",
+                stm32_mcu = "stm32f107"
+            )))]
+            syscfg_exticr_exti: periph.syscfg_exticr_exti,
+        };
+        Self { periph, int }
+    }
+
+    /// Creates a new [`Exti`].
+    ///
+    /// # Safety
+    ///
+    /// Some of the `Crt` register tokens can be still in use.
+    #[inline]
+    pub unsafe fn from_diverged(periph: ExtiDiverged<T>, int: I) -> Self {
+        Self { periph, int }
+    }
+
+    /// Releases the peripheral.
+    #[inline]
+    pub fn free(self) -> ExtiDiverged<T> {
+        self.periph
+    }
+
+    /// Routes the GPIO port `port` to the line. `0` is port A, `1` is port B,
+    /// and so on.
+    ///
+    /// SYSCFG (AFIO on STM32F1) clock must be enabled.
+    pub fn set_port(&self, port: u32) {
+        #[cfg(any(
+            stm32_mcu = "stm32f100",
+            stm32_mcu = "stm32f101",
+            stm32_mcu = "stm32f102",
+            stm32_mcu = "stm32f103",
+            stm32_mcu = "stm32f107"
+        ))]
+        self.periph.afio_exticr_exti.write_bits(port);
+        #[cfg(not(any(
+            stm32_mcu = "stm32f100",
+            stm32_mcu = "stm32f101",
+            stm32_mcu = "stm32f102",
+            stm32_mcu = "stm32f103",
+            stm32_mcu = "stm32f107"
+        )))]
+        self.periph.syscfg_exticr_exti.write_bits(port);
+    }
+
+    /// Selects the edges, which trigger the line.
+    pub fn set_trigger(&self, trigger: ExtiTrigger) {
+        match trigger {
+            ExtiTrigger::Rising => {
+                self.periph.exti_ftsr_ft.clear_bit();
+                self.periph.exti_rtsr_rt.set_bit();
+            }
+            ExtiTrigger::Falling => {
+                self.periph.exti_rtsr_rt.clear_bit();
+                self.periph.exti_ftsr_ft.set_bit();
+            }
+            ExtiTrigger::Any => {
+                self.periph.exti_rtsr_rt.set_bit();
+                self.periph.exti_ftsr_ft.set_bit();
+            }
+        }
+    }
+
+    /// Unmasks the interrupt request from the line.
+    pub fn unmask(&self) {
+        self.periph.exti_imr_im.set_bit();
+    }
+
+    /// Masks the interrupt request from the line.
+    pub fn mask(&self) {
+        self.periph.exti_imr_im.clear_bit();
+    }
+
+    /// Clears the pending bit of the line.
+    pub fn clear_pending(&self) {
+        clear_pif(&self.periph.exti_pr_pif);
+    }
+
+    /// Generates a software trigger event on the line.
+    pub fn software_trigger(&self) {
+        self.periph.exti_swier_swi.set_bit();
+    }
+
+    /// Returns a future, which resolves on the line trigger event.
+    pub fn triggered(&self) -> impl Future<Output = ()> {
+        self.triggered_with(|| ())
+    }
+
+    /// Returns a stream of the line trigger events.
+    pub fn stream(&self, capacity: usize) -> impl Stream<Item = Result<(), ExtiOverflow>> {
+        let overflow = |_| Err(ExtiOverflow);
+        let fib = self.stream_fib(|| ());
+        self.int.add_stream_ring(capacity, overflow, fib)
+    }
+
+    /// Returns a stream of the line trigger events.
+    pub fn stream_skip(&self, capacity: usize) -> impl Stream<Item = ()> {
+        let fib = self.stream_fib(|| ());
+        self.int.add_stream_ring_skip(capacity, fib)
+    }
+
+    pub(crate) fn triggered_with<F, E>(&self, mut f: F) -> impl Future<Output = E>
+    where
+        F: FnMut() -> E + Send + 'static,
+        E: Send + 'static,
+    {
+        let pif = self.periph.exti_pr_pif;
+        self.int.add_future(fib::new_fn(move || {
+            if pif.read_bit() {
+                clear_pif(&pif);
+                fib::Complete(f())
+            } else {
+                fib::Yielded(())
+            }
+        }))
+    }
+
+    pub(crate) fn stream_fib<F, E, R>(
+        &self,
+        mut f: F,
+    ) -> impl Fiber<Input = (), Yield = Option<E>, Return = R>
+    where
+        F: FnMut() -> E + Send + 'static,
+        E: Send + 'static,
+    {
+        let pif = self.periph.exti_pr_pif;
+        fib::new_fn(move || {
+            if pif.read_bit() {
+                clear_pif(&pif);
+                fib::Yielded(Some(f()))
+            } else {
+                fib::Yielded(None)
+            }
+        })
+    }
+}
+
+#[allow(missing_docs)]
+impl<T: ExtiMap, I: IntToken> Exti<T, I> {
+    #[inline]
+    pub fn int(&self) -> &I {
+        &self.int
+    }
+}
+
+/// Clears the pending bit of the line owned by `pif`.
+fn clear_pif<F: RegField<Crt>>(_pif: &F) {
+    // `EXTI_PR` is write-1-to-clear and zeros are ignored, so a store with only
+    // the `pif` bit set doesn't affect other lines. The read-modify-write
+    // methods of the token can't be used here, as they would write back and
+    // clear all pending bits of the register. The address and the offset come
+    // from the field token, which is owned by this line.
+    unsafe { write_volatile(F::Reg::ADDRESS as *mut u32, 1 << F::OFFSET) };
+}
+
+impl fmt::Display for ExtiOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "EXTI stream overflow.")
+    }
+}
@@ -0,0 +1,235 @@
+use super::{GpioPin, Input, OpenDrain, Output};
+use core::{
+    num::NonZeroUsize,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use drone_cortex_m::drv::timer::{Timer, TimerInterval};
+use drone_stm32_map::periph::gpio::pin::GpioPinMap;
+use futures::{future, prelude::*};
+
+/// Debounced input event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ButtonEvent {
+    /// The input became active.
+    Press,
+    /// The input became inactive.
+    Release,
+    /// The input stays active for [`DebounceConfig::long_press`] samples.
+    LongPress,
+}
+
+/// Debounced keypad event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeypadEvent {
+    /// Row index of the key.
+    pub row: usize,
+    /// Column index of the key.
+    pub col: usize,
+    /// Key event.
+    pub event: ButtonEvent,
+}
+
+/// Debouncer configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DebounceConfig {
+    /// Number of samples of the new level needed to accept a level change.
+    pub samples: u8,
+    /// Number of samples the input should stay active to produce
+    /// [`ButtonEvent::LongPress`]. `0` disables long press detection.
+    pub long_press: u32,
+    /// The input is active when the pin level is low.
+    pub active_low: bool,
+}
+
+/// Debouncing state machine of a single input.
+#[derive(Clone, Copy, Debug)]
+pub struct Debouncer {
+    config: DebounceConfig,
+    counter: u8,
+    active: bool,
+    held: u32,
+}
+
+/// Keypad matrix row, which is driven during scanning.
+pub trait KeypadRow {
+    /// Drives the row to the active level, or releases it.
+    fn drive(&self, active: bool);
+}
+
+/// Keypad matrix column, which is sampled during scanning.
+pub trait KeypadCol {
+    /// Returns `true` if the column is pulled to the active level.
+    fn sense(&self) -> bool;
+}
+
+/// Stream of debounced keypad events.
+pub struct KeypadStream<'a, S: Stream + Unpin> {
+    ticks: S,
+    rows: &'a [&'a dyn KeypadRow],
+    cols: &'a [&'a dyn KeypadCol],
+    keys: Vec<Debouncer>,
+    row: usize,
+    pending: Vec<KeypadEvent>,
+}
+
+impl Debouncer {
+    /// Creates a new [`Debouncer`] in the inactive state.
+    pub fn new(config: DebounceConfig) -> Self {
+        Self {
+            config,
+            counter: 0,
+            active: false,
+            held: 0,
+        }
+    }
+
+    /// Returns `true` if the debounced input is active.
+    #[inline]
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Feeds a new sample of the pin level, and returns an event if any.
+    pub fn update(&mut self, level: bool) -> Option<ButtonEvent> {
+        let samples = self.config.samples.max(1);
+        if level != self.config.active_low {
+            self.counter = self.counter.saturating_add(1).min(samples);
+        } else {
+            self.counter = self.counter.saturating_sub(1);
+        }
+        if self.active {
+            if self.counter == 0 {
+                self.active = false;
+                return Some(ButtonEvent::Release);
+            }
+            if self.config.long_press != 0 && self.held < self.config.long_press {
+                self.held += 1;
+                if self.held == self.config.long_press {
+                    return Some(ButtonEvent::LongPress);
+                }
+            }
+        } else if self.counter == samples {
+            self.active = true;
+            self.held = 0;
+            return Some(ButtonEvent::Press);
+        }
+        None
+    }
+}
+
+impl<T: GpioPinMap> GpioPin<T, Input> {
+    /// Returns a stream of debounced events of the pin, sampled every
+    /// `period` ticks of `timer`.
+    pub fn button_stream<'a>(
+        &'a self,
+        timer: &'a mut impl Timer,
+        period: u32,
+        config: DebounceConfig,
+    ) -> impl Stream<Item = ButtonEvent> + 'a {
+        let mut debouncer = Debouncer::new(config);
+        timer
+            .interval_skip(period)
+            .filter_map(move |_| future::ready(debouncer.update(self.is_high())))
+    }
+}
+
+/// Returns a stream of debounced events of a keypad matrix.
+///
+/// One row is driven active every `period` ticks of `timer`, and its columns
+/// are sampled on the next tick, so a full matrix scan takes `rows.len()`
+/// periods. Debouncer counters are advanced once per scan.
+/// [`DebounceConfig::active_low`] is ignored, the levels are defined by
+/// [`KeypadRow`] and [`KeypadCol`] implementations.
+pub fn keypad_stream<'a, T: Timer>(
+    timer: &'a mut T,
+    period: u32,
+    rows: &'a [&'a dyn KeypadRow],
+    cols: &'a [&'a dyn KeypadCol],
+    config: DebounceConfig,
+) -> KeypadStream<'a, TimerInterval<'a, T::Stop, NonZeroUsize>> {
+    let config = DebounceConfig {
+        active_low: false,
+        ..config
+    };
+    for row in rows {
+        row.drive(false);
+    }
+    if let Some(row) = rows.first() {
+        row.drive(true);
+    }
+    let mut keys = Vec::new();
+    keys.resize(rows.len() * cols.len(), Debouncer::new(config));
+    KeypadStream {
+        ticks: timer.interval_skip(period),
+        rows,
+        cols,
+        keys,
+        row: 0,
+        pending: Vec::with_capacity(cols.len()),
+    }
+}
+
+impl<'a, S: Stream + Unpin> KeypadStream<'a, S> {
+    fn scan(&mut self) {
+        if self.rows.is_empty() {
+            return;
+        }
+        let row = self.row;
+        let base = row * self.cols.len();
+        for (col, input) in self.cols.iter().enumerate().rev() {
+            if let Some(event) = self.keys[base + col].update(input.sense()) {
+                self.pending.push(KeypadEvent { row, col, event });
+            }
+        }
+        self.rows[row].drive(false);
+        self.row = (row + 1) % self.rows.len();
+        self.rows[self.row].drive(true);
+    }
+}
+
+impl<'a, S: Stream + Unpin> Stream for KeypadStream<'a, S> {
+    type Item = KeypadEvent;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let stream = self.get_mut();
+        loop {
+            if let Some(event) = stream.pending.pop() {
+                return Poll::Ready(Some(event));
+            }
+            match Pin::new(&mut stream.ticks).poll_next(cx) {
+                Poll::Ready(Some(_)) => stream.scan(),
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl<'a, S: Stream + Unpin> Drop for KeypadStream<'a, S> {
+    fn drop(&mut self) {
+        for row in self.rows {
+            row.drive(false);
+        }
+    }
+}
+
+impl<T: GpioPinMap> KeypadRow for GpioPin<T, Output<OpenDrain>> {
+    /// Drives the pin low when active, and releases it otherwise. The rows are
+    /// open-drain, so that two pressed keys in the same column do not short
+    /// the outputs.
+    fn drive(&self, active: bool) {
+        if active {
+            self.set_low();
+        } else {
+            self.set_high();
+        }
+    }
+}
+
+impl<T: GpioPinMap> KeypadCol for GpioPin<T, Input> {
+    /// Returns `true` if the pin level is low.
+    fn sense(&self) -> bool {
+        self.is_low()
+    }
+}
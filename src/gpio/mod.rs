@@ -0,0 +1,287 @@
+//! General-purpose I/O.
+
+use crate::common::DrvRcc;
+#[cfg(not(any(
+    stm32_mcu = "stm32f100",
+    stm32_mcu = "stm32f101",
+    stm32_mcu = "stm32f102",
+    stm32_mcu = "stm32f103",
+    stm32_mcu = "stm32f107"
+)))]
+use core::{fmt, marker::PhantomData};
+use drone_core::inventory::{self, Inventory0, Inventory1};
+use drone_cortex_m::reg::prelude::*;
+use drone_stm32_map::periph::gpio::{
+    head::{GpioHeadMap, GpioHeadPeriph},
+    pin::{GpioPinMap, GpioPinPeriph},
+};
+
+#[cfg(feature = "tim")]
+mod debounce;
+mod pin;
+
+#[cfg(feature = "tim")]
+pub use self::debounce::*;
+pub use self::pin::*;
+
+#[cfg(not(any(
+    stm32_mcu = "stm32f100",
+    stm32_mcu = "stm32f101",
+    stm32_mcu = "stm32f102",
+    stm32_mcu = "stm32f103",
+    stm32_mcu = "stm32f107"
+)))]
+/// Error returned when `GPIOx_LCKR_LCKK` flag is not set after the lock key
+/// sequence.
+#[derive(Debug)]
+pub struct GpioLockError;
+
+#[cfg(not(any(
+    stm32_mcu = "stm32f100",
+    stm32_mcu = "stm32f101",
+    stm32_mcu = "stm32f102",
+    stm32_mcu = "stm32f103",
+    stm32_mcu = "stm32f107"
+)))]
+/// Proof of the port pins configuration being locked until the next reset.
+pub struct GpioLock<T: GpioHeadMap> {
+    mask: u16,
+    _head: PhantomData<T>,
+}
+
+/// GPIO port head driver.
+pub struct GpioHead<T: GpioHeadMap>(Inventory0<GpioHeadEn<T>>);
+
+/// GPIO port head enabled driver.
+pub struct GpioHeadEn<T: GpioHeadMap> {
+    periph: GpioHeadPeriph<T>,
+}
+
+impl<T: GpioHeadMap> GpioHead<T> {
+    /// Creates a new [`GpioHead`].
+    #[inline]
+    pub fn new(periph: GpioHeadPeriph<T>) -> Self {
+        Self(Inventory0::new(GpioHeadEn { periph }))
+    }
+
+    /// Releases the peripheral.
+    #[inline]
+    pub fn free(self) -> GpioHeadPeriph<T> {
+        Inventory0::free(self.0).periph
+    }
+
+    /// Enables the port clock.
+    pub fn enable(&mut self) -> inventory::Guard<'_, GpioHeadEn<T>> {
+        self.setup();
+        Inventory0::guard(&mut self.0)
+    }
+
+    /// Enables the port clock.
+    pub fn into_enabled(self) -> Inventory1<GpioHeadEn<T>> {
+        self.setup();
+        let (enabled, token) = self.0.share1();
+        // To be recreated in `from_enabled()`.
+        drop(token);
+        enabled
+    }
+
+    /// Disables the port clock.
+    pub fn from_enabled(enabled: Inventory1<GpioHeadEn<T>>) -> Self {
+        // Restoring the token dropped in `into_enabled()`.
+        let token = unsafe { inventory::Token::new() };
+        let mut enabled = enabled.merge1(token);
+        Inventory0::teardown(&mut enabled);
+        Self(enabled)
+    }
+
+    fn setup(&self) {
+        let gpioen = &self.0.periph.rcc_busenr_gpioen;
+        if gpioen.read_bit() {
+            panic!("GPIO wasn't turned off");
+        }
+        gpioen.set_bit();
+    }
+}
+
+impl<T: GpioHeadMap> DrvRcc for GpioHead<T> {
+    #[inline]
+    fn reset(&mut self) {
+        self.0.reset();
+    }
+
+    #[inline]
+    fn disable_stop_mode(&self) {
+        self.0.disable_stop_mode();
+    }
+
+    #[inline]
+    fn enable_stop_mode(&self) {
+        self.0.enable_stop_mode();
+    }
+}
+
+impl<T: GpioHeadMap> GpioHeadEn<T> {
+    /// Creates a new pin driver of the port. The pin is switched to the input
+    /// mode.
+    pub fn pin<P: GpioPinMap<GpioHeadMap = T>>(
+        &self,
+        periph: GpioPinPeriph<P>,
+    ) -> GpioPin<P, Input> {
+        GpioPin::new(periph)
+    }
+
+    /// Drives the pins selected by `set` high and the pins selected by
+    /// `reset` low with a single atomic write to `GPIOx_BSRR`. If a pin is
+    /// selected by both masks, it is driven high.
+    #[inline]
+    pub fn set_reset(&self, set: u16, reset: u16) {
+        self.periph
+            .gpio_bsrr
+            .store_bits(u32::from(set) | u32::from(reset) << 16);
+    }
+
+    /// Drives the pins selected by `mask` high. Other pins are left intact.
+    #[inline]
+    pub fn set_pins(&self, mask: u16) {
+        self.set_reset(mask, 0);
+    }
+
+    /// Drives the pins selected by `mask` low. Other pins are left intact.
+    #[inline]
+    pub fn reset_pins(&self, mask: u16) {
+        self.set_reset(0, mask);
+    }
+
+    /// Returns the input levels of all port pins.
+    #[inline]
+    pub fn input(&self) -> u16 {
+        self.periph.gpio_idr.load_bits() as u16
+    }
+
+    /// Returns the output levels of all port pins.
+    #[inline]
+    pub fn output(&self) -> u16 {
+        self.periph.gpio_odr.load_bits() as u16
+    }
+
+    /// Writes `value` to a parallel bus formed by the pins selected by `mask`.
+    ///
+    /// The least significant bit of `value` goes to the lowest selected pin,
+    /// the next bit to the next selected pin, and so on. All bus pins are
+    /// updated with a single atomic write to `GPIOx_BSRR`.
+    pub fn write_bus(&self, mask: u16, value: u16) {
+        let set = bus_deposit(mask, value);
+        self.set_reset(set, mask & !set);
+    }
+
+    /// Reads a parallel bus formed by the pins selected by `mask`.
+    ///
+    /// The level of the lowest selected pin goes to the least significant bit
+    /// of the result, the next selected pin to the next bit, and so on.
+    pub fn read_bus(&self, mask: u16) -> u16 {
+        bus_extract(mask, self.input())
+    }
+}
+
+#[cfg(not(any(
+    stm32_mcu = "stm32f100",
+    stm32_mcu = "stm32f101",
+    stm32_mcu = "stm32f102",
+    stm32_mcu = "stm32f103",
+    stm32_mcu = "stm32f107"
+)))]
+impl<T: GpioHeadMap> GpioHeadEn<T> {
+    /// Locks the configuration of the pins selected by `mask` until the next
+    /// reset, using the `GPIOx_LCKR` key sequence.
+    pub fn lock(&self, mask: u16) -> Result<GpioLock<T>, GpioLockError> {
+        const LCKK: u32 = 1 << 16;
+        let lckr = &self.periph.gpio_lckr;
+        let lck = u32::from(mask);
+        lckr.store_bits(LCKK | lck);
+        lckr.store_bits(lck);
+        lckr.store_bits(LCKK | lck);
+        lckr.load_bits();
+        if lckr.load_bits() & LCKK == 0 {
+            return Err(GpioLockError);
+        }
+        Ok(GpioLock {
+            mask,
+            _head: PhantomData,
+        })
+    }
+}
+
+#[cfg(not(any(
+    stm32_mcu = "stm32f100",
+    stm32_mcu = "stm32f101",
+    stm32_mcu = "stm32f102",
+    stm32_mcu = "stm32f103",
+    stm32_mcu = "stm32f107"
+)))]
+impl<T: GpioHeadMap> GpioLock<T> {
+    /// Returns the mask of the locked pins.
+    #[inline]
+    pub fn mask(&self) -> u16 {
+        self.mask
+    }
+}
+
+impl<T: GpioHeadMap> inventory::Item for GpioHeadEn<T> {
+    fn teardown(&mut self, _token: &mut inventory::GuardToken<Self>) {
+        self.periph.rcc_busenr_gpioen.clear_bit()
+    }
+}
+
+impl<T: GpioHeadMap> DrvRcc for GpioHeadEn<T> {
+    fn reset(&mut self) {
+        self.periph.rcc_busrstr_gpiorst.set_bit();
+    }
+
+    fn disable_stop_mode(&self) {
+        self.periph.rcc_bussmenr_gpiosmen.clear_bit();
+    }
+
+    fn enable_stop_mode(&self) {
+        self.periph.rcc_bussmenr_gpiosmen.set_bit();
+    }
+}
+
+#[cfg(not(any(
+    stm32_mcu = "stm32f100",
+    stm32_mcu = "stm32f101",
+    stm32_mcu = "stm32f102",
+    stm32_mcu = "stm32f103",
+    stm32_mcu = "stm32f107"
+)))]
+impl fmt::Display for GpioLockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "GPIO lock key sequence failed.")
+    }
+}
+
+fn bus_deposit(mut mask: u16, mut value: u16) -> u16 {
+    let mut bits = 0;
+    while mask != 0 {
+        let pin = mask & mask.wrapping_neg();
+        if value & 1 != 0 {
+            bits |= pin;
+        }
+        value >>= 1;
+        mask &= !pin;
+    }
+    bits
+}
+
+fn bus_extract(mut mask: u16, bits: u16) -> u16 {
+    let mut value = 0;
+    let mut bit = 1;
+    while mask != 0 {
+        let pin = mask & mask.wrapping_neg();
+        if bits & pin != 0 {
+            value |= bit;
+        }
+        bit <<= 1;
+        mask &= !pin;
+    }
+    value
+}
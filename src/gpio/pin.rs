@@ -0,0 +1,660 @@
+#[cfg(feature = "exti")]
+use crate::exti::{Exti, ExtiOverflow, ExtiTrigger};
+use core::marker::PhantomData;
+use drone_cortex_m::reg::prelude::*;
+#[cfg(feature = "exti")]
+use drone_cortex_m::{fib::Fiber, thr::prelude::*};
+#[cfg(feature = "exti")]
+use drone_stm32_map::periph::exti::ExtiMap;
+use drone_stm32_map::periph::gpio::pin::{GpioPinMap, GpioPinPeriph};
+#[cfg(feature = "exti")]
+use futures::prelude::*;
+
+/// GPIO pin mode.
+pub trait PinMode: Send + Sync + 'static {}
+
+/// GPIO pin output type.
+pub trait PinOType: Send + Sync + 'static {
+    #[doc(hidden)]
+    const OPEN_DRAIN: bool;
+}
+
+/// GPIO pin alternate function.
+pub trait PinAf: Send + Sync + 'static {
+    #[doc(hidden)]
+    const NUM: u32;
+}
+
+/// Input mode.
+pub struct Input;
+
+/// General purpose output mode.
+pub struct Output<O: PinOType>(PhantomData<O>);
+
+/// Alternate function mode.
+pub struct Alternate<A: PinAf, O: PinOType>(PhantomData<(A, O)>);
+
+/// Analog mode.
+pub struct Analog;
+
+/// Push-pull output type.
+pub struct PushPull;
+
+/// Open-drain output type.
+pub struct OpenDrain;
+
+/// GPIO pin pull-up/pull-down configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GpioPull {
+    /// No pull-up, pull-down.
+    None,
+    /// Pull-up.
+    Up,
+    /// Pull-down.
+    Down,
+}
+
+/// GPIO pin output speed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GpioSpeed {
+    /// Low speed.
+    Low,
+    /// Medium speed.
+    Medium,
+    /// High speed.
+    High,
+    /// Very high speed. Same as [`GpioSpeed::High`] on STM32F1.
+    VeryHigh,
+}
+
+/// GPIO pin signal edge.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GpioEdge {
+    /// Transition from low to high level.
+    Rising,
+    /// Transition from high to low level.
+    Falling,
+}
+
+/// GPIO pin driver.
+pub struct GpioPin<T: GpioPinMap, M: PinMode> {
+    periph: GpioPinDiverged<T>,
+    _mode: PhantomData<M>,
+}
+
+/// GPIO pin diverged peripheral.
+#[allow(missing_docs)]
+pub struct GpioPinDiverged<T: GpioPinMap> {
+    #[cfg(any(
+        stm32_mcu = "stm32f100",
+        stm32_mcu = "stm32f101",
+        stm32_mcu = "stm32f102",
+        stm32_mcu = "stm32f103",
+        stm32_mcu = "stm32f107"
+    ))]
+    pub gpio_cr_cnf: T::SGpioCrCnf,
+    #[cfg(any(
+        stm32_mcu = "stm32f100",
+        stm32_mcu = "stm32f101",
+        stm32_mcu = "stm32f102",
+        stm32_mcu = "stm32f103",
+        stm32_mcu = "stm32f107"
+    ))]
+    pub gpio_cr_mode: T::SGpioCrMode,
+    #[cfg(not(any(
+        stm32_mcu = "stm32f100",
+        stm32_mcu = "stm32f101",
+        stm32_mcu = "stm32f102",
+        stm32_mcu = "stm32f103",
+        stm32_mcu = "stm32f107"
+    )))]
+    pub gpio_moder_moder: T::SGpioModerModer,
+    #[cfg(not(any(
+        stm32_mcu = "stm32f100",
+        stm32_mcu = "stm32f101",
+        stm32_mcu = "stm32f102",
+        stm32_mcu = "stm32f103",
+        stm32_mcu = "stm32f107"
+    )))]
+    pub gpio_otyper_ot: T::SGpioOtyperOt,
+    #[cfg(not(any(
+        stm32_mcu = "stm32f100",
+        stm32_mcu = "stm32f101",
+        stm32_mcu = "stm32f102",
+        stm32_mcu = "stm32f103",
+        stm32_mcu = "stm32f107"
+    )))]
+    pub gpio_ospeedr_ospeedr: T::SGpioOspeedrOspeedr,
+    #[cfg(not(any(
+        stm32_mcu = "stm32f100",
+        stm32_mcu = "stm32f101",
+        stm32_mcu = "stm32f102",
+        stm32_mcu = "stm32f103",
+        stm32_mcu = "stm32f107"
+    )))]
+    pub gpio_pupdr_pupdr: T::SGpioPupdrPupdr,
+    #[cfg(not(any(
+        stm32_mcu = "stm32f100",
+        stm32_mcu = "stm32f101",
+        stm32_mcu = "stm32f102",
+        stm32_mcu = "stm32f103",
+        stm32_mcu = "stm32f107"
+    )))]
+    pub gpio_afr_afr: T::SGpioAfrAfr,
+    pub gpio_idr_idr: T::CGpioIdrIdr,
+    pub gpio_odr_odr: T::SGpioOdrOdr,
+    pub gpio_bsrr_bs: T::SGpioBsrrBs,
+    pub gpio_bsrr_br: T::SGpioBsrrBr,
+}
+
+impl PinMode for Input {}
+impl<O: PinOType> PinMode for Output<O> {}
+impl<A: PinAf, O: PinOType> PinMode for Alternate<A, O> {}
+impl PinMode for Analog {}
+
+impl PinOType for PushPull {
+    const OPEN_DRAIN: bool = false;
+}
+
+impl PinOType for OpenDrain {
+    const OPEN_DRAIN: bool = true;
+}
+
+macro_rules! pin_af {
+    ($($(#[$attr:meta])* $name:ident => $num:expr,)*) => {
+        $(
+            $(#[$attr])*
+            pub struct $name;
+
+            impl PinAf for $name {
+                const NUM: u32 = $num;
+            }
+        )*
+    };
+}
+
+#[cfg(any(
+    stm32_mcu = "stm32f100",
+    stm32_mcu = "stm32f101",
+    stm32_mcu = "stm32f102",
+    stm32_mcu = "stm32f103",
+    stm32_mcu = "stm32f107"
+))]
+pin_af! {
+    /// Alternate function selected by the AFIO remapping.
+    AfRemap => 0,
+}
+
+#[cfg(not(any(
+    stm32_mcu = "stm32f100",
+    stm32_mcu = "stm32f101",
+    stm32_mcu = "stm32f102",
+    stm32_mcu = "stm32f103",
+    stm32_mcu = "stm32f107"
+)))]
+pin_af! {
+    /// Alternate function 0.
+    Af0 => 0,
+    /// Alternate function 1.
+    Af1 => 1,
+    /// Alternate function 2.
+    Af2 => 2,
+    /// Alternate function 3.
+    Af3 => 3,
+    /// Alternate function 4.
+    Af4 => 4,
+    /// Alternate function 5.
+    Af5 => 5,
+    /// Alternate function 6.
+    Af6 => 6,
+    /// Alternate function 7.
+    Af7 => 7,
+    /// Alternate function 8.
+    Af8 => 8,
+    /// Alternate function 9.
+    Af9 => 9,
+    /// Alternate function 10.
+    Af10 => 10,
+    /// Alternate function 11.
+    Af11 => 11,
+    /// Alternate function 12.
+    Af12 => 12,
+    /// Alternate function 13.
+    Af13 => 13,
+    /// Alternate function 14.
+    Af14 => 14,
+    /// Alternate function 15.
+    Af15 => 15,
+}
+
+impl<T: GpioPinMap> GpioPin<T, Input> {
+    /// Creates a new [`GpioPin`] in the input mode.
+    ///
+    /// The port clock must be enabled. Prefer
+    /// [`GpioHeadEn::pin`](super::GpioHeadEn::pin).
+    #[inline]
+    pub fn new(periph: GpioPinPeriph<T>) -> Self {
+        let periph = GpioPinDiverged {
+            #[cfg(any(
+                stm32_mcu = "stm32f100",
+                stm32_mcu = "stm32f101",
+                stm32_mcu = "stm32f102",
+                stm32_mcu = "stm32f103",
+                stm32_mcu = "stm32f107"
+            ))]
+            gpio_cr_cnf: periph.gpio_cr_cnf,
+            #[cfg(any(
+                stm32_mcu = "stm32f100",
+                stm32_mcu = "stm32f101",
+                stm32_mcu = "stm32f102",
+                stm32_mcu = "stm32f103",
+                stm32_mcu = "stm32f107"
+            ))]
+            gpio_cr_mode: periph.gpio_cr_mode,
+            #[cfg(not(any(
+                stm32_mcu = "stm32f100",
+                stm32_mcu = "stm32f101",
+                stm32_mcu = "stm32f102",
+                stm32_mcu = "stm32f103",
+                stm32_mcu = "stm32f107"
+            )))]
+            gpio_moder_moder: periph.gpio_moder_moder,
+            #[cfg(not(any(
+                stm32_mcu = "stm32f100",
+                stm32_mcu = "stm32f101",
+                stm32_mcu = "stm32f102",
+                stm32_mcu = "stm32f103",
+                stm32_mcu = "stm32f107"
+            )))]
+            gpio_otyper_ot: periph.gpio_otyper_ot,
+            #[cfg(not(any(
+                stm32_mcu = "stm32f100",
+                stm32_mcu = "stm32f101",
+                stm32_mcu = "stm32f102",
+                stm32_mcu = "stm32f103",
+                stm32_mcu = "stm32f107"
+            )))]
+            gpio_ospeedr_ospeedr: periph.gpio_ospeedr_ospeedr,
+            #[cfg(not(any(
+                stm32_mcu = "stm32f100",
+                stm32_mcu = "stm32f101",
+                stm32_mcu = "stm32f102",
+                stm32_mcu = "stm32f103",
+                stm32_mcu = "stm32f107"
+            )))]
+            gpio_pupdr_pupdr: periph.gpio_pupdr_pupdr,
+            #[cfg(not(any(
+                stm32_mcu = "stm32f100",
+                stm32_mcu = "stm32f101",
+                stm32_mcu = "stm32f102",
+                stm32_mcu = "stm32f103",
+                stm32_mcu = "stm32f107"
+            )))]
+            gpio_afr_afr: periph.gpio_afr_afr,
+            gpio_idr_idr: periph.gpio_idr_idr.into_copy(),
+            gpio_odr_odr: periph.gpio_odr_odr,
+            gpio_bsrr_bs: periph.gpio_bsrr_bs,
+            gpio_bsrr_br: periph.gpio_bsrr_br,
+        };
+        let pin = Self::with_mode(periph);
+        pin.mode_input();
+        pin
+    }
+
+    /// Creates a new [`GpioPin`] in the input mode.
+    ///
+    /// # Safety
+    ///
+    /// Some of the `Crt` register tokens can be still in use.
+    #[inline]
+    pub unsafe fn from_diverged(periph: GpioPinDiverged<T>) -> Self {
+        let pin = Self::with_mode(periph);
+        pin.mode_input();
+        pin
+    }
+}
+
+impl<T: GpioPinMap, M: PinMode> GpioPin<T, M> {
+    /// Releases the peripheral.
+    #[inline]
+    pub fn free(self) -> GpioPinDiverged<T> {
+        self.periph
+    }
+
+    /// Switches the pin to the input mode.
+    pub fn into_input(self) -> GpioPin<T, Input> {
+        self.mode_input();
+        GpioPin::with_mode(self.periph)
+    }
+
+    /// Switches the pin to the push-pull output mode.
+    pub fn into_push_pull_output(self) -> GpioPin<T, Output<PushPull>> {
+        self.into_output()
+    }
+
+    /// Switches the pin to the open-drain output mode.
+    pub fn into_open_drain_output(self) -> GpioPin<T, Output<OpenDrain>> {
+        self.into_output()
+    }
+
+    /// Switches the pin to the general purpose output mode with the output
+    /// type `O`.
+    pub fn into_output<O: PinOType>(self) -> GpioPin<T, Output<O>> {
+        self.mode_output(O::OPEN_DRAIN);
+        GpioPin::with_mode(self.periph)
+    }
+
+    /// Switches the pin to the alternate function `A` with the output type
+    /// `O`.
+    pub fn into_alternate<A: PinAf, O: PinOType>(self) -> GpioPin<T, Alternate<A, O>> {
+        self.mode_alternate(A::NUM, O::OPEN_DRAIN);
+        GpioPin::with_mode(self.periph)
+    }
+
+    /// Switches the pin to the analog mode.
+    pub fn into_analog(self) -> GpioPin<T, Analog> {
+        self.mode_analog();
+        GpioPin::with_mode(self.periph)
+    }
+
+    #[inline]
+    fn with_mode(periph: GpioPinDiverged<T>) -> Self {
+        Self {
+            periph,
+            _mode: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn idr(&self) -> bool {
+        self.periph.gpio_idr_idr.read_bit()
+    }
+}
+
+impl<T: GpioPinMap> GpioPin<T, Input> {
+    /// Returns `true` if the pin input level is high.
+    #[inline]
+    pub fn is_high(&self) -> bool {
+        self.idr()
+    }
+
+    /// Returns `true` if the pin input level is low.
+    #[inline]
+    pub fn is_low(&self) -> bool {
+        !self.idr()
+    }
+}
+
+#[cfg(feature = "exti")]
+impl<T: GpioPinMap> GpioPin<T, Input> {
+    /// Returns a future, which resolves on the next rising edge of the pin.
+    ///
+    /// `exti` must be the line with the same number as the pin, routed to the
+    /// pin port with [`Exti::set_port`].
+    pub fn wait_for_rising_edge<E: ExtiMap>(
+        &self,
+        exti: &Exti<E, impl IntToken>,
+    ) -> impl Future<Output = ()> {
+        exti.set_trigger(ExtiTrigger::Rising);
+        exti.clear_pending();
+        let triggered = exti.triggered();
+        exti.unmask();
+        triggered
+    }
+
+    /// Returns a future, which resolves on the next falling edge of the pin.
+    ///
+    /// `exti` must be the line with the same number as the pin, routed to the
+    /// pin port with [`Exti::set_port`].
+    pub fn wait_for_falling_edge<E: ExtiMap>(
+        &self,
+        exti: &Exti<E, impl IntToken>,
+    ) -> impl Future<Output = ()> {
+        exti.set_trigger(ExtiTrigger::Falling);
+        exti.clear_pending();
+        let triggered = exti.triggered();
+        exti.unmask();
+        triggered
+    }
+
+    /// Returns a future, which resolves on the next edge of the pin.
+    ///
+    /// `exti` must be the line with the same number as the pin, routed to the
+    /// pin port with [`Exti::set_port`].
+    pub fn wait_for_any_edge<E: ExtiMap>(
+        &self,
+        exti: &Exti<E, impl IntToken>,
+    ) -> impl Future<Output = GpioEdge> {
+        let idr = self.periph.gpio_idr_idr;
+        exti.set_trigger(ExtiTrigger::Any);
+        exti.clear_pending();
+        let triggered = exti.triggered_with(move || edge(idr.read_bit()));
+        exti.unmask();
+        triggered
+    }
+
+    /// Returns a stream of edges of the pin.
+    ///
+    /// `exti` must be the line with the same number as the pin, routed to the
+    /// pin port with [`Exti::set_port`].
+    pub fn edge_stream<E: ExtiMap>(
+        &self,
+        exti: &Exti<E, impl IntToken>,
+        capacity: usize,
+    ) -> impl Stream<Item = Result<GpioEdge, ExtiOverflow>> {
+        let overflow = |_| Err(ExtiOverflow);
+        let fib = self.edge_stream_fib(exti);
+        let stream = exti.int().add_stream_ring(capacity, overflow, fib);
+        exti.unmask();
+        stream
+    }
+
+    /// Returns a stream of edges of the pin.
+    ///
+    /// `exti` must be the line with the same number as the pin, routed to the
+    /// pin port with [`Exti::set_port`].
+    pub fn edge_stream_skip<E: ExtiMap>(
+        &self,
+        exti: &Exti<E, impl IntToken>,
+        capacity: usize,
+    ) -> impl Stream<Item = GpioEdge> {
+        let fib = self.edge_stream_fib(exti);
+        let stream = exti.int().add_stream_ring_skip(capacity, fib);
+        exti.unmask();
+        stream
+    }
+
+    fn edge_stream_fib<E: ExtiMap, R>(
+        &self,
+        exti: &Exti<E, impl IntToken>,
+    ) -> impl Fiber<Input = (), Yield = Option<GpioEdge>, Return = R> {
+        let idr = self.periph.gpio_idr_idr;
+        exti.set_trigger(ExtiTrigger::Any);
+        exti.clear_pending();
+        exti.stream_fib(move || edge(idr.read_bit()))
+    }
+}
+
+impl<T: GpioPinMap, O: PinOType> GpioPin<T, Output<O>> {
+    /// Drives the pin high.
+    #[inline]
+    pub fn set_high(&self) {
+        self.periph.gpio_bsrr_bs.set_bit();
+    }
+
+    /// Drives the pin low.
+    #[inline]
+    pub fn set_low(&self) {
+        self.periph.gpio_bsrr_br.set_bit();
+    }
+
+    /// Inverts the pin output level.
+    #[inline]
+    pub fn toggle(&self) {
+        if self.is_set_high() {
+            self.set_low();
+        } else {
+            self.set_high();
+        }
+    }
+
+    /// Returns `true` if the pin output is driven high.
+    #[inline]
+    pub fn is_set_high(&self) -> bool {
+        self.periph.gpio_odr_odr.read_bit()
+    }
+
+    /// Returns `true` if the pin input level is high.
+    #[inline]
+    pub fn is_high(&self) -> bool {
+        self.idr()
+    }
+
+    /// Returns `true` if the pin input level is low.
+    #[inline]
+    pub fn is_low(&self) -> bool {
+        !self.idr()
+    }
+
+    /// Sets the pin output speed.
+    pub fn set_speed(&self, speed: GpioSpeed) {
+        self.speed(speed);
+    }
+}
+
+impl<T: GpioPinMap, A: PinAf, O: PinOType> GpioPin<T, Alternate<A, O>> {
+    /// Sets the pin output speed.
+    pub fn set_speed(&self, speed: GpioSpeed) {
+        self.speed(speed);
+    }
+}
+
+#[cfg(any(
+    stm32_mcu = "stm32f100",
+    stm32_mcu = "stm32f101",
+    stm32_mcu = "stm32f102",
+    stm32_mcu = "stm32f103",
+    stm32_mcu = "stm32f107"
+))]
+impl<T: GpioPinMap> GpioPin<T, Input> {
+    /// Sets the pin pull-up/pull-down configuration.
+    pub fn set_pull(&self, pull: GpioPull) {
+        match pull {
+            GpioPull::None => {
+                self.periph.gpio_cr_cnf.write_bits(0b01);
+            }
+            GpioPull::Up => {
+                self.periph.gpio_bsrr_bs.set_bit();
+                self.periph.gpio_cr_cnf.write_bits(0b10);
+            }
+            GpioPull::Down => {
+                self.periph.gpio_bsrr_br.set_bit();
+                self.periph.gpio_cr_cnf.write_bits(0b10);
+            }
+        }
+    }
+}
+
+#[cfg(any(
+    stm32_mcu = "stm32f100",
+    stm32_mcu = "stm32f101",
+    stm32_mcu = "stm32f102",
+    stm32_mcu = "stm32f103",
+    stm32_mcu = "stm32f107"
+))]
+impl<T: GpioPinMap, M: PinMode> GpioPin<T, M> {
+    fn mode_input(&self) {
+        self.periph.gpio_cr_mode.write_bits(0b00);
+        self.periph.gpio_cr_cnf.write_bits(0b01);
+    }
+
+    fn mode_output(&self, open_drain: bool) {
+        self.periph
+            .gpio_cr_cnf
+            .write_bits(if open_drain { 0b01 } else { 0b00 });
+        self.periph.gpio_cr_mode.write_bits(0b10);
+    }
+
+    fn mode_alternate(&self, _af: u32, open_drain: bool) {
+        self.periph
+            .gpio_cr_cnf
+            .write_bits(if open_drain { 0b11 } else { 0b10 });
+        self.periph.gpio_cr_mode.write_bits(0b10);
+    }
+
+    fn mode_analog(&self) {
+        self.periph.gpio_cr_mode.write_bits(0b00);
+        self.periph.gpio_cr_cnf.write_bits(0b00);
+    }
+
+    fn speed(&self, speed: GpioSpeed) {
+        self.periph.gpio_cr_mode.write_bits(match speed {
+            GpioSpeed::Low => 0b10,
+            GpioSpeed::Medium => 0b01,
+            GpioSpeed::High | GpioSpeed::VeryHigh => 0b11,
+        });
+    }
+}
+
+#[cfg(not(any(
+    stm32_mcu = "stm32f100",
+    stm32_mcu = "stm32f101",
+    stm32_mcu = "stm32f102",
+    stm32_mcu = "stm32f103",
+    stm32_mcu = "stm32f107"
+)))]
+impl<T: GpioPinMap, M: PinMode> GpioPin<T, M> {
+    /// Sets the pin pull-up/pull-down configuration.
+    pub fn set_pull(&self, pull: GpioPull) {
+        self.periph.gpio_pupdr_pupdr.write_bits(match pull {
+            GpioPull::None => 0b00,
+            GpioPull::Up => 0b01,
+            GpioPull::Down => 0b10,
+        });
+    }
+
+    fn mode_input(&self) {
+        self.periph.gpio_moder_moder.write_bits(0b00);
+    }
+
+    fn mode_output(&self, open_drain: bool) {
+        self.otype(open_drain);
+        self.periph.gpio_moder_moder.write_bits(0b01);
+    }
+
+    fn mode_alternate(&self, af: u32, open_drain: bool) {
+        self.otype(open_drain);
+        self.periph.gpio_afr_afr.write_bits(af);
+        self.periph.gpio_moder_moder.write_bits(0b10);
+    }
+
+    fn mode_analog(&self) {
+        self.periph.gpio_moder_moder.write_bits(0b11);
+    }
+
+    fn otype(&self, open_drain: bool) {
+        if open_drain {
+            self.periph.gpio_otyper_ot.set_bit();
+        } else {
+            self.periph.gpio_otyper_ot.clear_bit();
+        }
+    }
+
+    fn speed(&self, speed: GpioSpeed) {
+        self.periph.gpio_ospeedr_ospeedr.write_bits(match speed {
+            GpioSpeed::Low => 0b00,
+            GpioSpeed::Medium => 0b01,
+            GpioSpeed::High => 0b10,
+            GpioSpeed::VeryHigh => 0b11,
+        });
+    }
+}
+
+#[cfg(feature = "exti")]
+fn edge(level: bool) -> GpioEdge {
+    if level {
+        GpioEdge::Rising
+    } else {
+        GpioEdge::Falling
+    }
+}
@@ -0,0 +1,404 @@
+use super::{take_error, I2CAddr, I2CBreak, I2CEn, I2CError};
+use crate::dma::{DmaReadBuffer, DmaWriteBuffer};
+use core::fmt;
+use drone_cortex_m::{fib, reg::prelude::*, thr::prelude::*};
+use drone_stm32_map::periph::i2c::{traits::*, I2CMap};
+use futures::{
+    future::{self, Either},
+    prelude::*,
+};
+
+/// I2C interrupt-driven transfer error.
+#[derive(Debug)]
+pub enum I2CIntError {
+    /// I2C transfer failure.
+    I2CBreak(I2CBreak),
+    /// I2C error.
+    I2CError(I2CError),
+    /// Block byte count is zero or doesn't fit in the buffer.
+    BlockCount,
+}
+
+/// Memory region of an interrupt-driven transfer.
+pub(super) struct IntBuf {
+    pub(super) addr: usize,
+    pub(super) len: usize,
+}
+
+/// Disables the transfer interrupts, and closes the session if the transfer
+/// is dropped before completion.
+struct I2CIntGuard<'a, T: I2CMap, Ev: IntToken, Er: IntToken> {
+    i2c: &'a I2CEn<T, Ev, Er>,
+    done: bool,
+}
+
+impl<T: I2CMap, Ev: IntToken, Er: IntToken> I2CEn<T, Ev, Er> {
+    /// Reads bytes to `buf` from `slave_addr` from the event interrupt. Leaves
+    /// the session open.
+    ///
+    /// The returned future gives back the buffer.
+    pub fn read_int<'a, B: DmaWriteBuffer<Word = u8>>(
+        &'a self,
+        buf: B,
+        slave_addr: impl Into<I2CAddr>,
+        i2c_cr1_val: T::I2CCr1Val,
+        i2c_cr2_val: T::I2CCr2Val,
+    ) -> impl Future<Output = (B, Result<(), I2CIntError>)> + 'a {
+        self.read_int_impl(buf, slave_addr.into(), i2c_cr1_val, i2c_cr2_val, false)
+    }
+
+    /// Reads bytes to `buf` from `slave_addr` from the event interrupt. Closes
+    /// the session afterwards.
+    ///
+    /// The returned future gives back the buffer.
+    pub fn read_and_stop_int<'a, B: DmaWriteBuffer<Word = u8>>(
+        &'a self,
+        buf: B,
+        slave_addr: impl Into<I2CAddr>,
+        i2c_cr1_val: T::I2CCr1Val,
+        i2c_cr2_val: T::I2CCr2Val,
+    ) -> impl Future<Output = (B, Result<(), I2CIntError>)> + 'a {
+        self.read_int_impl(buf, slave_addr.into(), i2c_cr1_val, i2c_cr2_val, true)
+    }
+
+    /// Writes bytes from `buf` to `slave_addr` from the event interrupt.
+    /// Leaves the session open.
+    ///
+    /// The returned future gives back the buffer.
+    pub fn write_int<'a, B: DmaReadBuffer<Word = u8>>(
+        &'a self,
+        buf: B,
+        slave_addr: impl Into<I2CAddr>,
+        i2c_cr1_val: T::I2CCr1Val,
+        i2c_cr2_val: T::I2CCr2Val,
+    ) -> impl Future<Output = (B, Result<(), I2CIntError>)> + 'a {
+        self.write_int_impl(buf, slave_addr.into(), i2c_cr1_val, i2c_cr2_val, false)
+    }
+
+    /// Writes bytes from `buf` to `slave_addr` from the event interrupt.
+    /// Closes the session afterwards.
+    ///
+    /// The returned future gives back the buffer.
+    pub fn write_and_stop_int<'a, B: DmaReadBuffer<Word = u8>>(
+        &'a self,
+        buf: B,
+        slave_addr: impl Into<I2CAddr>,
+        i2c_cr1_val: T::I2CCr1Val,
+        i2c_cr2_val: T::I2CCr2Val,
+    ) -> impl Future<Output = (B, Result<(), I2CIntError>)> + 'a {
+        self.write_int_impl(buf, slave_addr.into(), i2c_cr1_val, i2c_cr2_val, true)
+    }
+
+    async fn read_int_impl<B: DmaWriteBuffer<Word = u8>>(
+        &self,
+        mut buf: B,
+        slave_addr: I2CAddr,
+        i2c_cr1_val: T::I2CCr1Val,
+        i2c_cr2_val: T::I2CCr2Val,
+        autoend: bool,
+    ) -> (B, Result<(), I2CIntError>) {
+        let (addr, len) = buf.dma_write_buffer();
+        let buf_region = IntBuf {
+            addr: addr as usize,
+            len,
+        };
+        let result = self
+            .int_rx(
+                buf_region,
+                slave_addr,
+                i2c_cr1_val,
+                i2c_cr2_val,
+                autoend,
+                false,
+            )
+            .await;
+        (buf, result.map(drop))
+    }
+
+    async fn write_int_impl<B: DmaReadBuffer<Word = u8>>(
+        &self,
+        buf: B,
+        slave_addr: I2CAddr,
+        i2c_cr1_val: T::I2CCr1Val,
+        i2c_cr2_val: T::I2CCr2Val,
+        autoend: bool,
+    ) -> (B, Result<(), I2CIntError>) {
+        let (addr, len) = buf.dma_read_buffer();
+        let buf_region = IntBuf {
+            addr: addr as usize,
+            len,
+        };
+        let result = self
+            .int_tx(buf_region, slave_addr, i2c_cr1_val, i2c_cr2_val, autoend)
+            .await;
+        (buf, result)
+    }
+
+    /// Writes bytes from `buf` to `slave_addr` from the event interrupt. If
+    /// `PECBYTE` is set in `i2c_cr2_val`, the PEC byte is transmitted after
+    /// the data.
+    pub(super) async fn int_tx(
+        &self,
+        buf: IntBuf,
+        slave_addr: I2CAddr,
+        mut i2c_cr1_val: T::I2CCr1Val,
+        mut i2c_cr2_val: T::I2CCr2Val,
+        autoend: bool,
+    ) -> Result<(), I2CIntError> {
+        let IntBuf { addr, len } = buf;
+        let nbytes = len + usize::from(self.periph.i2c_cr2.pecbyte().read(&i2c_cr2_val));
+        let cr1 = self.periph.i2c_cr1;
+        let cr2 = self.periph.i2c_cr2;
+        let txis = *self.periph.i2c_isr.txis();
+        let tc = *self.periph.i2c_isr.tc();
+        let tcr = *self.periph.i2c_isr.tcr();
+        let nackf = *self.periph.i2c_isr.nackf();
+        let stopf = *self.periph.i2c_isr.stopf();
+        let nackcf = *self.periph.i2c_icr.nackcf();
+        let stopcf = *self.periph.i2c_icr.stopcf();
+        let txdata = *self.periph.i2c_txdr.txdata();
+        let mut count = 0;
+        let mut remaining = nbytes.saturating_sub(255);
+        let mut nack = false;
+        let tx = self.int_ev.add_future(fib::new_fn(move || {
+            if txis.read_bit_band() && count < len {
+                let byte = unsafe { *(addr as *const u8).add(count) };
+                txdata.write_bits(u32::from(byte));
+                count += 1;
+            }
+            if tcr.read_bit_band() {
+                reload::<T>(cr2, &mut remaining, autoend);
+            }
+            if nackf.read_bit_band() {
+                nackcf.set_bit_band();
+                nack = true;
+            }
+            if stopf.read_bit_band() {
+                stopcf.set_bit_band();
+                fib::Complete(if nack { Err(I2CBreak::Nack) } else { Ok(()) })
+            } else if tc.read_bit_band() {
+                // The flag is cleared only by the next START or STOP.
+                cr1.tcie().clear_bit();
+                fib::Complete(Ok(()))
+            } else {
+                fib::Yielded(())
+            }
+        }));
+        self.periph.i2c_cr1.store_val({
+            self.periph.i2c_cr1.pe().set(&mut i2c_cr1_val);
+            self.periph.i2c_cr1.txdmaen().clear(&mut i2c_cr1_val);
+            self.periph.i2c_cr1.rxdmaen().clear(&mut i2c_cr1_val);
+            self.periph.i2c_cr1.txie().set(&mut i2c_cr1_val);
+            self.periph.i2c_cr1.tcie().set(&mut i2c_cr1_val);
+            i2c_cr1_val
+        });
+        let mut guard = I2CIntGuard {
+            i2c: self,
+            done: false,
+        };
+        self.set_i2c_cr2(&mut i2c_cr2_val, slave_addr, autoend, nbytes, true);
+        self.periph.i2c_cr2.store_val(i2c_cr2_val);
+        let result = self.int_session(tx).await;
+        guard.done = true;
+        drop(guard);
+        Ok(result??)
+    }
+
+    /// Reads bytes to `buf` from `slave_addr` from the event interrupt. If
+    /// `PECBYTE` is set in `i2c_cr2_val`, the PEC byte is received and checked
+    /// after the data. Returns the number of bytes stored to `buf`.
+    ///
+    /// If `block` is set, the first received byte is the number of the
+    /// following bytes. The count byte is stored to `buf` too. If the count is
+    /// zero or the block doesn't fit in `buf`, a single byte is received and
+    /// NACKed to end the transfer, and [`I2CIntError::BlockCount`] is
+    /// returned.
+    pub(super) async fn int_rx(
+        &self,
+        buf: IntBuf,
+        slave_addr: I2CAddr,
+        mut i2c_cr1_val: T::I2CCr1Val,
+        mut i2c_cr2_val: T::I2CCr2Val,
+        autoend: bool,
+        block: bool,
+    ) -> Result<usize, I2CIntError> {
+        let IntBuf { addr, len } = buf;
+        let pecbyte = usize::from(self.periph.i2c_cr2.pecbyte().read(&i2c_cr2_val));
+        let nbytes = if block { 1 } else { len + pecbyte };
+        let cr1 = self.periph.i2c_cr1;
+        let cr2 = self.periph.i2c_cr2;
+        let rxne = *self.periph.i2c_isr.rxne();
+        let tc = *self.periph.i2c_isr.tc();
+        let tcr = *self.periph.i2c_isr.tcr();
+        let nackf = *self.periph.i2c_isr.nackf();
+        let stopf = *self.periph.i2c_isr.stopf();
+        let nackcf = *self.periph.i2c_icr.nackcf();
+        let stopcf = *self.periph.i2c_icr.stopcf();
+        let rxdata = *self.periph.i2c_rxdr.rxdata();
+        let mut count = 0;
+        let mut received = 0;
+        let mut total = if block { 1 } else { len };
+        let mut remaining = nbytes.saturating_sub(255);
+        let mut nack = false;
+        let mut invalid = false;
+        let rx = self.int_ev.add_future(fib::new_fn(move || {
+            if rxne.read_bit_band() {
+                let byte = rxdata.read_bits() as u8;
+                // The PEC byte and bytes beyond the buffer are discarded.
+                if received < total && count < len {
+                    unsafe { *(addr as *mut u8).add(count) = byte };
+                    count += 1;
+                }
+                if block && received == 0 {
+                    let block_len = usize::from(byte);
+                    if block_len == 0 || block_len >= len {
+                        invalid = true;
+                        remaining = 1;
+                    } else {
+                        total += block_len;
+                        remaining = block_len + pecbyte;
+                    }
+                }
+                received += 1;
+            }
+            if tcr.read_bit_band() && received > 0 {
+                reload::<T>(cr2, &mut remaining, autoend || invalid);
+            }
+            if nackf.read_bit_band() {
+                nackcf.set_bit_band();
+                nack = true;
+            }
+            if stopf.read_bit_band() {
+                stopcf.set_bit_band();
+                fib::Complete(if invalid {
+                    Err(I2CIntError::BlockCount)
+                } else if nack {
+                    Err(I2CBreak::Nack.into())
+                } else {
+                    Ok(count)
+                })
+            } else if tc.read_bit_band() {
+                // The flag is cleared only by the next START or STOP.
+                cr1.tcie().clear_bit();
+                fib::Complete(if invalid {
+                    Err(I2CIntError::BlockCount)
+                } else {
+                    Ok(count)
+                })
+            } else {
+                fib::Yielded(())
+            }
+        }));
+        self.periph.i2c_cr1.store_val({
+            self.periph.i2c_cr1.pe().set(&mut i2c_cr1_val);
+            self.periph.i2c_cr1.txdmaen().clear(&mut i2c_cr1_val);
+            self.periph.i2c_cr1.rxdmaen().clear(&mut i2c_cr1_val);
+            self.periph.i2c_cr1.rxie().set(&mut i2c_cr1_val);
+            self.periph.i2c_cr1.tcie().set(&mut i2c_cr1_val);
+            i2c_cr1_val
+        });
+        let mut guard = I2CIntGuard {
+            i2c: self,
+            done: false,
+        };
+        self.set_i2c_cr2(&mut i2c_cr2_val, slave_addr, autoend, nbytes, false);
+        if block {
+            // The rest of the block is reloaded after the count byte.
+            self.periph.i2c_cr2.reload().set(&mut i2c_cr2_val);
+            self.periph.i2c_cr2.autoend().clear(&mut i2c_cr2_val);
+        }
+        self.periph.i2c_cr2.store_val(i2c_cr2_val);
+        let result = self.int_session(rx).await;
+        guard.done = true;
+        drop(guard);
+        Ok(result??)
+    }
+
+    /// Runs the event interrupt future `end` until completion or I2C error.
+    pub(super) async fn int_session<F: Future + Unpin>(
+        &self,
+        end: F,
+    ) -> Result<F::Output, I2CError> {
+        let i2c_error = self.transfer_error();
+        self.periph.i2c_cr1.modify(|r| {
+            self.periph.i2c_cr1.errie().set(r);
+            self.periph.i2c_cr1.nackie().set(r);
+            self.periph.i2c_cr1.stopie().set(r);
+        });
+        let result = match future::select(end, i2c_error).await {
+            Either::Left((output, i2c_error)) => {
+                drop(i2c_error);
+                self.int_er.trigger();
+                // An error, which is raised along with the end event, can be
+                // still pending in the error interrupt.
+                take_error::<T>(self.periph.i2c_isr, self.periph.i2c_icr).map_or(Ok(output), Err)
+            }
+            Either::Right((i2c_error, end)) => {
+                drop(end);
+                self.int_ev.trigger();
+                Err(i2c_error)
+            }
+        };
+        self.periph.i2c_cr1.modify(|r| {
+            self.periph.i2c_cr1.nackie().clear(r);
+            self.periph.i2c_cr1.stopie().clear(r);
+        });
+        result
+    }
+}
+
+impl<T: I2CMap, Ev: IntToken, Er: IntToken> Drop for I2CIntGuard<'_, T, Ev, Er> {
+    fn drop(&mut self) {
+        self.i2c.periph.i2c_cr1.modify(|r| {
+            self.i2c.periph.i2c_cr1.txie().clear(r);
+            self.i2c.periph.i2c_cr1.rxie().clear(r);
+            self.i2c.periph.i2c_cr1.tcie().clear(r);
+            self.i2c.periph.i2c_cr1.nackie().clear(r);
+            self.i2c.periph.i2c_cr1.stopie().clear(r);
+            self.i2c.periph.i2c_cr1.errie().clear(r);
+        });
+        if !self.done {
+            self.i2c.stop_on_error();
+        }
+    }
+}
+
+/// Writes the next `NBYTES` chunk of `remaining` bytes on a transfer complete
+/// reload event.
+fn reload<T: I2CMap>(cr2: T::CI2CCr2, remaining: &mut usize, autoend: bool) {
+    let chunk = (*remaining).min(255);
+    *remaining -= chunk;
+    cr2.modify(|r| {
+        cr2.nbytes().write(r, chunk as u32);
+        if *remaining > 0 {
+            cr2.reload().set(r);
+        } else {
+            cr2.reload().clear(r);
+            if autoend {
+                cr2.autoend().set(r);
+            }
+        }
+    });
+}
+
+impl From<I2CBreak> for I2CIntError {
+    fn from(err: I2CBreak) -> Self {
+        Self::I2CBreak(err)
+    }
+}
+
+impl From<I2CError> for I2CIntError {
+    fn from(err: I2CError) -> Self {
+        Self::I2CError(err)
+    }
+}
+
+impl fmt::Display for I2CIntError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::I2CBreak(err) => write!(f, "I2C failure: {}", err),
+            Self::I2CError(err) => write!(f, "I2C error: {}", err),
+            Self::BlockCount => write!(f, "I2C block count is invalid."),
+        }
+    }
+}
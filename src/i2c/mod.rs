@@ -0,0 +1,1132 @@
+//! Inter-Integrated Circuit.
+
+#[cfg(any(
+    stm32_mcu = "stm32l4x1",
+    stm32_mcu = "stm32l4x2",
+    stm32_mcu = "stm32l4x3",
+    stm32_mcu = "stm32l4x5",
+    stm32_mcu = "stm32l4x6",
+    stm32_mcu = "stm32l4r5",
+    stm32_mcu = "stm32l4r7",
+    stm32_mcu = "stm32l4r9",
+    stm32_mcu = "stm32l4s5",
+    stm32_mcu = "stm32l4s7",
+    stm32_mcu = "stm32l4s9"
+))]
+use crate::common::{DmaRxReq, DmaTxReq};
+#[cfg(feature = "gpio")]
+use crate::gpio::{Alternate, GpioPin, OpenDrain, PinAf, PinMode};
+use crate::{
+    common::{DrvClockSel, DrvDmaRx, DrvDmaTx, DrvRcc},
+    dma::{DmaChEn, DmaDir, DmaReadBuffer, DmaTransferError, DmaWriteBuffer},
+    select3::{Output3, Select3},
+};
+use core::fmt;
+use drone_core::inventory::{self, Inventory0, Inventory1};
+use drone_cortex_m::{fib, reg::prelude::*, thr::prelude::*};
+#[cfg(feature = "gpio")]
+use drone_stm32_map::periph::gpio::pin::GpioPinMap;
+use drone_stm32_map::periph::{
+    dma::ch::DmaChMap,
+    i2c::{traits::*, I2CMap, I2CPeriph},
+};
+use futures::prelude::*;
+
+mod int;
+mod recovery;
+mod smbus;
+mod target;
+mod timing;
+mod transaction;
+
+pub use self::{int::*, recovery::*, smbus::*, target::*, timing::*, transaction::*};
+
+/// I2C DMA error.
+#[derive(Debug)]
+pub enum I2CDmaError {
+    /// DMA error.
+    Dma(DmaTransferError),
+    /// I2C transfer failure.
+    I2CBreak(I2CBreak),
+    /// I2C error.
+    I2CError(I2CError),
+}
+
+/// I2C error.
+#[derive(Debug)]
+pub enum I2CError {
+    /// Bus error.
+    Berr,
+    /// Overrun/Underrun.
+    Ovr,
+    /// Arbitration lost.
+    Arlo,
+    /// Timeout or t_low detection flag.
+    Timeout,
+    /// SMBus alert.
+    Alert,
+    /// PEC error in reception.
+    Pecerr,
+}
+
+/// I2C device address.
+///
+/// A `u8` value converts into a 7-bit address.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum I2CAddr {
+    /// 7-bit address.
+    Bits7(u8),
+    /// 10-bit address.
+    Bits10(u16),
+}
+
+/// I2C transfer failure event.
+#[derive(Debug)]
+pub enum I2CBreak {
+    /// NACK reception.
+    Nack,
+    /// Stop reception.
+    Stop,
+}
+
+/// GPIO pin, which can carry the SCL signal of the I2C `T`.
+#[cfg(feature = "gpio")]
+pub trait I2CSclPin<T: I2CMap>: GpioPinMap {
+    /// Alternate function of the signal.
+    type Af: PinAf;
+}
+
+/// GPIO pin, which can carry the SDA signal of the I2C `T`.
+#[cfg(feature = "gpio")]
+pub trait I2CSdaPin<T: I2CMap>: GpioPinMap {
+    /// Alternate function of the signal.
+    type Af: PinAf;
+}
+
+/// I2C driver.
+pub struct I2C<T: I2CMap, Ev: IntToken, Er: IntToken>(Inventory0<I2CEn<T, Ev, Er>>);
+
+/// I2C enabled driver.
+pub struct I2CEn<T: I2CMap, Ev: IntToken, Er: IntToken> {
+    periph: I2CDiverged<T>,
+    int_ev: Ev,
+    int_er: Er,
+}
+
+/// I2C diverged peripheral.
+#[allow(missing_docs)]
+pub struct I2CDiverged<T: I2CMap> {
+    pub rcc_busenr_i2cen: T::SRccBusenrI2Cen,
+    pub rcc_busrstr_i2crst: T::SRccBusrstrI2Crst,
+    pub rcc_bussmenr_i2csmen: T::SRccBussmenrI2Csmen,
+    pub rcc_ccipr_i2csel: T::SRccCciprI2Csel,
+    pub i2c_cr1: T::CI2CCr1,
+    pub i2c_cr2: T::CI2CCr2,
+    pub i2c_oar1: T::SI2COar1,
+    pub i2c_oar2: T::SI2COar2,
+    pub i2c_timingr: T::SI2CTimingr,
+    pub i2c_timeoutr: T::SI2CTimeoutr,
+    pub i2c_isr: T::CI2CIsr,
+    pub i2c_icr: T::CI2CIcr,
+    pub i2c_pecr: T::SI2CPecr,
+    pub i2c_rxdr: T::CI2CRxdr,
+    pub i2c_txdr: T::CI2CTxdr,
+}
+
+impl<T: I2CMap, Ev: IntToken, Er: IntToken> I2C<T, Ev, Er> {
+    /// Creates a new [`I2C`].
+    #[inline]
+    pub fn new(periph: I2CPeriph<T>, int_ev: Ev, int_er: Er) -> Self {
+        let periph = I2CDiverged {
+            rcc_busenr_i2cen: periph.rcc_busenr_i2cen,
+            rcc_busrstr_i2crst: periph.rcc_busrstr_i2crst,
+            rcc_bussmenr_i2csmen: periph.rcc_bussmenr_i2csmen,
+            rcc_ccipr_i2csel: periph.rcc_ccipr_i2csel,
+            i2c_cr1: periph.i2c_cr1.into_copy(),
+            i2c_cr2: periph.i2c_cr2.into_copy(),
+            i2c_oar1: periph.i2c_oar1,
+            i2c_oar2: periph.i2c_oar2,
+            i2c_timingr: periph.i2c_timingr,
+            i2c_timeoutr: periph.i2c_timeoutr,
+            i2c_isr: periph.i2c_isr.into_copy(),
+            i2c_icr: periph.i2c_icr.into_copy(),
+            i2c_pecr: periph.i2c_pecr,
+            i2c_rxdr: periph.i2c_rxdr.into_copy(),
+            i2c_txdr: periph.i2c_txdr.into_copy(),
+        };
+        Self(Inventory0::new(I2CEn {
+            periph,
+            int_ev,
+            int_er,
+        }))
+    }
+
+    /// Creates a new [`I2C`].
+    ///
+    /// # Safety
+    ///
+    /// Some of the `Crt` register tokens can be still in use.
+    #[inline]
+    pub unsafe fn from_diverged(periph: I2CDiverged<T>, int_ev: Ev, int_er: Er) -> Self {
+        Self(Inventory0::new(I2CEn {
+            periph,
+            int_ev,
+            int_er,
+        }))
+    }
+
+    /// Releases the peripheral.
+    #[inline]
+    pub fn free(self) -> I2CDiverged<T> {
+        Inventory0::free(self.0).periph
+    }
+
+    /// Enables I2C clock.
+    pub fn enable(&mut self) -> inventory::Guard<'_, I2CEn<T, Ev, Er>> {
+        self.setup();
+        Inventory0::guard(&mut self.0)
+    }
+
+    /// Enables I2C clock.
+    pub fn into_enabled(self) -> Inventory1<I2CEn<T, Ev, Er>> {
+        self.setup();
+        let (enabled, token) = self.0.share1();
+        // To be recreated in `from_enabled()`.
+        drop(token);
+        enabled
+    }
+
+    /// Disables I2C clock.
+    pub fn from_enabled(enabled: Inventory1<I2CEn<T, Ev, Er>>) -> Self {
+        // Restoring the token dropped in `into_enabled()`.
+        let token = unsafe { inventory::Token::new() };
+        let mut enabled = enabled.merge1(token);
+        Inventory0::teardown(&mut enabled);
+        Self(enabled)
+    }
+
+    fn setup(&self) {
+        let i2cen = &self.0.periph.rcc_busenr_i2cen;
+        if i2cen.read_bit() {
+            panic!("I2C wasn't turned off");
+        }
+        i2cen.set_bit();
+    }
+}
+
+impl<T: I2CMap, Ev: IntToken, Er: IntToken> I2CEn<T, Ev, Er> {
+    /// Reads bytes to `buf` from `slave_addr`. Leaves the session open.
+    ///
+    /// The returned future gives back the DMA channel, the buffer, and the
+    /// number of bytes transferred. Dropping the future before completion
+    /// aborts the DMA transfer and closes the session with a STOP condition.
+    pub fn read<'a, Rx: DmaChMap, RxI: IntToken, B: DmaWriteBuffer<Word = u8>>(
+        &'a self,
+        dma_rx: DmaChEn<Rx, RxI>,
+        buf: B,
+        slave_addr: impl Into<I2CAddr>,
+        i2c_cr1_val: T::I2CCr1Val,
+        i2c_cr2_val: T::I2CCr2Val,
+    ) -> impl Future<Output = (DmaChEn<Rx, RxI>, B, usize, Result<(), I2CDmaError>)> + 'a {
+        self.read_impl(
+            dma_rx,
+            buf,
+            slave_addr.into(),
+            i2c_cr1_val,
+            i2c_cr2_val,
+            false,
+        )
+    }
+
+    /// Reads bytes to `buf` from `slave_addr`. Closes the session afterwards.
+    ///
+    /// The returned future gives back the DMA channel, the buffer, and the
+    /// number of bytes transferred. Dropping the future before completion
+    /// aborts the DMA transfer and closes the session with a STOP condition.
+    pub fn read_and_stop<'a, Rx: DmaChMap, RxI: IntToken, B: DmaWriteBuffer<Word = u8>>(
+        &'a self,
+        dma_rx: DmaChEn<Rx, RxI>,
+        buf: B,
+        slave_addr: impl Into<I2CAddr>,
+        i2c_cr1_val: T::I2CCr1Val,
+        i2c_cr2_val: T::I2CCr2Val,
+    ) -> impl Future<Output = (DmaChEn<Rx, RxI>, B, usize, Result<(), I2CDmaError>)> + 'a {
+        self.read_impl(
+            dma_rx,
+            buf,
+            slave_addr.into(),
+            i2c_cr1_val,
+            i2c_cr2_val,
+            true,
+        )
+    }
+
+    /// Writes bytes from `buf` to `slave_addr`. Leaves the session open.
+    ///
+    /// The returned future gives back the DMA channel, the buffer, and the
+    /// number of bytes transferred. Dropping the future before completion
+    /// aborts the DMA transfer and closes the session with a STOP condition.
+    pub fn write<'a, Tx: DmaChMap, TxI: IntToken, B: DmaReadBuffer<Word = u8>>(
+        &'a self,
+        dma_tx: DmaChEn<Tx, TxI>,
+        buf: B,
+        slave_addr: impl Into<I2CAddr>,
+        i2c_cr1_val: T::I2CCr1Val,
+        i2c_cr2_val: T::I2CCr2Val,
+    ) -> impl Future<Output = (DmaChEn<Tx, TxI>, B, usize, Result<(), I2CDmaError>)> + 'a {
+        self.write_impl(
+            dma_tx,
+            buf,
+            slave_addr.into(),
+            i2c_cr1_val,
+            i2c_cr2_val,
+            false,
+        )
+    }
+
+    /// Writes bytes from `buf` to `slave_addr`. Closes the session afterwards.
+    ///
+    /// The returned future gives back the DMA channel, the buffer, and the
+    /// number of bytes transferred. Dropping the future before completion
+    /// aborts the DMA transfer and closes the session with a STOP condition.
+    pub fn write_and_stop<'a, Tx: DmaChMap, TxI: IntToken, B: DmaReadBuffer<Word = u8>>(
+        &'a self,
+        dma_tx: DmaChEn<Tx, TxI>,
+        buf: B,
+        slave_addr: impl Into<I2CAddr>,
+        i2c_cr1_val: T::I2CCr1Val,
+        i2c_cr2_val: T::I2CCr2Val,
+    ) -> impl Future<Output = (DmaChEn<Tx, TxI>, B, usize, Result<(), I2CDmaError>)> + 'a {
+        self.write_impl(
+            dma_tx,
+            buf,
+            slave_addr.into(),
+            i2c_cr1_val,
+            i2c_cr2_val,
+            true,
+        )
+    }
+
+    /// Configures the bus timing and the noise filters according to `config`.
+    ///
+    /// The peripheral is disabled in the process.
+    pub fn set_timing(&self, config: &I2CTimingConfig) -> Result<(), I2CTimingError> {
+        let timing = config.timing()?;
+        self.periph.i2c_cr1.pe().clear_bit();
+        self.periph.i2c_cr1.modify(|r| {
+            if config.analog_filter {
+                self.periph.i2c_cr1.anfoff().clear(r);
+            } else {
+                self.periph.i2c_cr1.anfoff().set(r);
+            }
+            self.periph.i2c_cr1.dnf().write(r, config.digital_filter);
+        });
+        self.periph.i2c_timingr.store_val({
+            let mut val = self.periph.i2c_timingr.default_val();
+            self.periph
+                .i2c_timingr
+                .presc()
+                .write(&mut val, timing.presc);
+            self.periph
+                .i2c_timingr
+                .scldel()
+                .write(&mut val, timing.scldel);
+            self.periph
+                .i2c_timingr
+                .sdadel()
+                .write(&mut val, timing.sdadel);
+            self.periph.i2c_timingr.sclh().write(&mut val, timing.sclh);
+            self.periph.i2c_timingr.scll().write(&mut val, timing.scll);
+            val
+        });
+        Ok(())
+    }
+
+    /// Returns a future, which resolves on I2C error event.
+    pub fn transfer_error(&self) -> impl Future<Output = I2CError> {
+        let isr = self.periph.i2c_isr;
+        let icr = self.periph.i2c_icr;
+        self.int_er.add_future(fib::new_fn(move || {
+            if let Some(err) = take_error::<T>(isr, icr) {
+                fib::Complete(err)
+            } else {
+                fib::Yielded(())
+            }
+        }))
+    }
+
+    /// Returns a future, which resolves on I2C transfer failure event.
+    pub fn transfer_break(&self) -> impl Future<Output = I2CBreak> {
+        let nackf = *self.periph.i2c_isr.nackf();
+        let stopf = *self.periph.i2c_isr.stopf();
+        let nackcf = *self.periph.i2c_icr.nackcf();
+        let stopcf = *self.periph.i2c_icr.stopcf();
+        self.int_ev.add_future(fib::new_fn(move || {
+            if nackf.read_bit_band() {
+                nackcf.set_bit_band();
+                fib::Complete(I2CBreak::Nack)
+            } else if stopf.read_bit_band() {
+                stopcf.set_bit_band();
+                fib::Complete(I2CBreak::Stop)
+            } else {
+                fib::Yielded(())
+            }
+        }))
+    }
+
+    async fn read_impl<Rx: DmaChMap, RxI: IntToken, B: DmaWriteBuffer<Word = u8>>(
+        &self,
+        dma_rx: DmaChEn<Rx, RxI>,
+        mut buf: B,
+        slave_addr: I2CAddr,
+        mut i2c_cr1_val: T::I2CCr1Val,
+        mut i2c_cr2_val: T::I2CCr2Val,
+        autoend: bool,
+    ) -> (DmaChEn<Rx, RxI>, B, usize, Result<(), I2CDmaError>) {
+        let (_, len) = buf.dma_write_buffer();
+        let rx_ccr = dma_rx.config().minc(true).teie(true).tcie(true).build();
+        let mut dma_rx = dma_rx.start_rx(buf, rx_ccr);
+        let dma_rx_complete = dma_rx.transfer_complete();
+        self.periph.i2c_cr1.store_val({
+            self.periph.i2c_cr1.pe().set(&mut i2c_cr1_val);
+            self.periph.i2c_cr1.errie().set(&mut i2c_cr1_val);
+            self.periph.i2c_cr1.nackie().set(&mut i2c_cr1_val);
+            self.periph.i2c_cr1.rxdmaen().set(&mut i2c_cr1_val);
+            if len > 255 {
+                self.periph.i2c_cr1.tcie().set(&mut i2c_cr1_val);
+            }
+            i2c_cr1_val
+        });
+        let mut guard = I2CDmaGuard {
+            i2c: self,
+            done: false,
+        };
+        let i2c_break = self.transfer_break();
+        let i2c_error = self.transfer_error();
+        let i2c_reload = self.transfer_reload(len, autoend);
+        self.set_i2c_cr2(&mut i2c_cr2_val, slave_addr, autoend, len, false);
+        self.periph.i2c_cr2.store_val(i2c_cr2_val);
+        let result = match Select3::new(dma_rx_complete, i2c_break, i2c_error).await {
+            Output3::A(dma_rx_res, i2c_break, i2c_error) => {
+                drop(i2c_break);
+                drop(i2c_error);
+                self.int_ev.trigger();
+                self.int_er.trigger();
+                dma_rx_res.map_err(Into::into)
+            }
+            Output3::B(dma_rx_fut, i2c_break, i2c_error) => {
+                drop(dma_rx_fut);
+                drop(i2c_error);
+                dma_rx.ch().int().trigger();
+                self.int_er.trigger();
+                Err(i2c_break.into())
+            }
+            Output3::C(dma_rx_fut, i2c_break, i2c_error) => {
+                drop(dma_rx_fut);
+                drop(i2c_break);
+                dma_rx.ch().int().trigger();
+                self.int_ev.trigger();
+                Err(i2c_error.into())
+            }
+        };
+        drop(i2c_reload);
+        guard.done = true;
+        drop(guard);
+        let (dma_rx, buf, transferred) = dma_rx.abort();
+        (dma_rx, buf, transferred, result)
+    }
+
+    async fn write_impl<Tx: DmaChMap, TxI: IntToken, B: DmaReadBuffer<Word = u8>>(
+        &self,
+        dma_tx: DmaChEn<Tx, TxI>,
+        buf: B,
+        slave_addr: I2CAddr,
+        mut i2c_cr1_val: T::I2CCr1Val,
+        mut i2c_cr2_val: T::I2CCr2Val,
+        autoend: bool,
+    ) -> (DmaChEn<Tx, TxI>, B, usize, Result<(), I2CDmaError>) {
+        let (_, len) = buf.dma_read_buffer();
+        let tx_ccr = dma_tx
+            .config()
+            .dir(DmaDir::MemToPeriph)
+            .minc(true)
+            .teie(true)
+            .tcie(true)
+            .build();
+        let mut dma_tx = dma_tx.start_tx(buf, tx_ccr);
+        let dma_tx_complete = dma_tx.transfer_complete();
+        self.periph.i2c_cr1.store_val({
+            self.periph.i2c_cr1.pe().set(&mut i2c_cr1_val);
+            self.periph.i2c_cr1.errie().set(&mut i2c_cr1_val);
+            self.periph.i2c_cr1.nackie().set(&mut i2c_cr1_val);
+            self.periph.i2c_cr1.txdmaen().set(&mut i2c_cr1_val);
+            if len > 255 {
+                self.periph.i2c_cr1.tcie().set(&mut i2c_cr1_val);
+            }
+            i2c_cr1_val
+        });
+        let mut guard = I2CDmaGuard {
+            i2c: self,
+            done: false,
+        };
+        let i2c_break = self.transfer_break();
+        let i2c_error = self.transfer_error();
+        let i2c_reload = self.transfer_reload(len, autoend);
+        self.set_i2c_cr2(&mut i2c_cr2_val, slave_addr, autoend, len, true);
+        self.periph.i2c_cr2.store_val(i2c_cr2_val);
+        let result = match Select3::new(dma_tx_complete, i2c_break, i2c_error).await {
+            Output3::A(dma_tx_res, i2c_break, i2c_error) => {
+                drop(i2c_break);
+                drop(i2c_error);
+                self.int_ev.trigger();
+                self.int_er.trigger();
+                dma_tx_res.map_err(Into::into)
+            }
+            Output3::B(dma_tx_fut, i2c_break, i2c_error) => {
+                drop(dma_tx_fut);
+                drop(i2c_error);
+                dma_tx.ch().int().trigger();
+                self.int_er.trigger();
+                Err(i2c_break.into())
+            }
+            Output3::C(dma_tx_fut, i2c_break, i2c_error) => {
+                drop(dma_tx_fut);
+                drop(i2c_break);
+                dma_tx.ch().int().trigger();
+                self.int_ev.trigger();
+                Err(i2c_error.into())
+            }
+        };
+        drop(i2c_reload);
+        guard.done = true;
+        drop(guard);
+        let (dma_tx, buf, transferred) = dma_tx.abort();
+        (dma_tx, buf, transferred, result)
+    }
+
+    /// Returns a future, which reloads `NBYTES` on each transfer complete
+    /// reload event of a transfer of `nbytes` bytes. Returns `None` if the
+    /// transfer fits in a single `NBYTES` value.
+    fn transfer_reload(&self, nbytes: usize, autoend: bool) -> Option<impl Future<Output = ()>> {
+        if nbytes <= 255 {
+            return None;
+        }
+        let cr1 = self.periph.i2c_cr1;
+        let cr2 = self.periph.i2c_cr2;
+        let tcr = *self.periph.i2c_isr.tcr();
+        let mut remaining = nbytes - 255;
+        Some(self.int_ev.add_future(fib::new_fn(move || {
+            if !tcr.read_bit_band() {
+                return fib::Yielded(());
+            }
+            let chunk = remaining.min(255);
+            remaining -= chunk;
+            cr2.modify(|r| {
+                cr2.nbytes().write(r, chunk as u32);
+                if remaining > 0 {
+                    cr2.reload().set(r);
+                } else {
+                    cr2.reload().clear(r);
+                    if autoend {
+                        cr2.autoend().set(r);
+                    }
+                }
+            });
+            if remaining > 0 {
+                fib::Yielded(())
+            } else {
+                // The final transfer complete event is not serviced.
+                cr1.tcie().clear_bit();
+                fib::Complete(())
+            }
+        })))
+    }
+
+    /// Sets the address, the direction, and the size of the next transfer in
+    /// `val`, and requests a START condition.
+    ///
+    /// A read from a 10-bit address after a write to the same address in the
+    /// same session is started with a repeated START and only the 10-bit
+    /// header, as the target is still addressed.
+    fn set_i2c_cr2(
+        &self,
+        val: &mut T::I2CCr2Val,
+        slave_addr: I2CAddr,
+        autoend: bool,
+        nbytes: usize,
+        write: bool,
+    ) {
+        // The current `I2C_CR2` still holds the address and the direction of
+        // the previous transfer.
+        let cr2 = self.periph.i2c_cr2.load_val();
+        match slave_addr {
+            I2CAddr::Bits7(addr) => {
+                self.periph.i2c_cr2.add10().clear(val);
+                self.periph.i2c_cr2.sadd().write(val, u32::from(addr) << 1);
+                self.periph.i2c_cr2.head10r().clear(val);
+            }
+            I2CAddr::Bits10(addr) => {
+                self.periph.i2c_cr2.add10().set(val);
+                self.periph.i2c_cr2.sadd().write(val, u32::from(addr));
+                let after_write = self.periph.i2c_isr.busy().read_bit_band()
+                    && self.periph.i2c_cr2.add10().read(&cr2)
+                    && self.periph.i2c_cr2.sadd().read(&cr2) == u32::from(addr)
+                    && !self.periph.i2c_cr2.rd_wrn().read(&cr2);
+                if !write && after_write {
+                    self.periph.i2c_cr2.head10r().set(val);
+                } else {
+                    self.periph.i2c_cr2.head10r().clear(val);
+                }
+            }
+        }
+        if write {
+            self.periph.i2c_cr2.rd_wrn().clear(val);
+        } else {
+            self.periph.i2c_cr2.rd_wrn().set(val);
+        }
+        self.periph
+            .i2c_cr2
+            .nbytes()
+            .write(val, nbytes.min(255) as u32);
+        if nbytes > 255 {
+            self.periph.i2c_cr2.reload().set(val);
+            self.periph.i2c_cr2.autoend().clear(val);
+        } else {
+            self.periph.i2c_cr2.reload().clear(val);
+            if autoend {
+                self.periph.i2c_cr2.autoend().set(val);
+            } else {
+                self.periph.i2c_cr2.autoend().clear(val);
+            }
+        }
+        self.periph.i2c_cr2.start().set(val);
+    }
+}
+
+/// Checks and clears the I2C error flags, returning the first set one.
+pub(super) fn take_error<T: I2CMap>(isr: T::CI2CIsr, icr: T::CI2CIcr) -> Option<I2CError> {
+    if isr.berr().read_bit_band() {
+        icr.berrcf().set_bit_band();
+        Some(I2CError::Berr)
+    } else if isr.ovr().read_bit_band() {
+        icr.ovrcf().set_bit_band();
+        Some(I2CError::Ovr)
+    } else if isr.arlo().read_bit_band() {
+        icr.arlocf().set_bit_band();
+        Some(I2CError::Arlo)
+    } else if isr.timeout().read_bit_band() {
+        icr.timoutcf().set_bit_band();
+        Some(I2CError::Timeout)
+    } else if isr.alert().read_bit_band() {
+        icr.alertcf().set_bit_band();
+        Some(I2CError::Alert)
+    } else if isr.pecerr().read_bit_band() {
+        icr.peccf().set_bit_band();
+        Some(I2CError::Pecerr)
+    } else {
+        None
+    }
+}
+
+/// Disables the I2C DMA requests of a DMA transfer, and closes the session if
+/// the transfer is dropped before completion.
+struct I2CDmaGuard<'a, T: I2CMap, Ev: IntToken, Er: IntToken> {
+    i2c: &'a I2CEn<T, Ev, Er>,
+    done: bool,
+}
+
+impl<T: I2CMap, Ev: IntToken, Er: IntToken> Drop for I2CDmaGuard<'_, T, Ev, Er> {
+    fn drop(&mut self) {
+        self.i2c.periph.i2c_cr1.modify(|r| {
+            self.i2c.periph.i2c_cr1.rxdmaen().clear(r);
+            self.i2c.periph.i2c_cr1.txdmaen().clear(r);
+        });
+        if !self.done {
+            self.i2c.stop_on_error();
+        }
+    }
+}
+
+#[allow(missing_docs)]
+impl<T: I2CMap, Ev: IntToken, Er: IntToken> I2CEn<T, Ev, Er> {
+    #[inline]
+    pub fn cr1(&self) -> &T::CI2CCr1 {
+        &self.periph.i2c_cr1
+    }
+
+    #[inline]
+    pub fn cr2(&self) -> &T::CI2CCr2 {
+        &self.periph.i2c_cr2
+    }
+
+    #[inline]
+    pub fn timingr(&self) -> &T::SI2CTimingr {
+        &self.periph.i2c_timingr
+    }
+}
+
+#[cfg(feature = "gpio")]
+impl<T: I2CMap, Ev: IntToken, Er: IntToken> I2CEn<T, Ev, Er> {
+    /// Switches `scl` and `sda` pins to the open-drain alternate functions of
+    /// the I2C.
+    pub fn setup_pins<Scl, Sda>(
+        &self,
+        scl: GpioPin<Scl, impl PinMode>,
+        sda: GpioPin<Sda, impl PinMode>,
+    ) -> (
+        GpioPin<Scl, Alternate<Scl::Af, OpenDrain>>,
+        GpioPin<Sda, Alternate<Sda::Af, OpenDrain>>,
+    )
+    where
+        Scl: I2CSclPin<T>,
+        Sda: I2CSdaPin<T>,
+    {
+        (scl.into_alternate(), sda.into_alternate())
+    }
+}
+
+impl<T: I2CMap, Ev: IntToken, Er: IntToken> inventory::Item for I2CEn<T, Ev, Er> {
+    fn teardown(&mut self, _token: &mut inventory::GuardToken<Self>) {
+        self.periph.rcc_busenr_i2cen.clear_bit()
+    }
+}
+
+impl<T, Ev, Er, Rx> DrvDmaRx<Rx> for I2C<T, Ev, Er>
+where
+    T: I2CMap,
+    Ev: IntToken,
+    Er: IntToken,
+    Rx: DmaChMap,
+{
+    #[inline]
+    fn dma_rx_paddr_init(&self, dma_rx: &DmaChEn<Rx, impl IntToken>) {
+        self.0.dma_rx_paddr_init(dma_rx);
+    }
+}
+
+impl<T, Ev, Er, Tx> DrvDmaTx<Tx> for I2C<T, Ev, Er>
+where
+    T: I2CMap,
+    Ev: IntToken,
+    Er: IntToken,
+    Tx: DmaChMap,
+{
+    #[inline]
+    fn dma_tx_paddr_init(&self, dma_tx: &DmaChEn<Tx, impl IntToken>) {
+        self.0.dma_tx_paddr_init(dma_tx);
+    }
+}
+
+impl<T, Ev, Er, Rx> DrvDmaRx<Rx> for I2CEn<T, Ev, Er>
+where
+    T: I2CMap,
+    Ev: IntToken,
+    Er: IntToken,
+    Rx: DmaChMap,
+{
+    fn dma_rx_paddr_init(&self, dma_rx: &DmaChEn<Rx, impl IntToken>) {
+        unsafe { dma_rx.set_paddr(self.periph.i2c_rxdr.as_ptr()) };
+    }
+}
+
+impl<T, Ev, Er, Tx> DrvDmaTx<Tx> for I2CEn<T, Ev, Er>
+where
+    T: I2CMap,
+    Ev: IntToken,
+    Er: IntToken,
+    Tx: DmaChMap,
+{
+    fn dma_tx_paddr_init(&self, dma_tx: &DmaChEn<Tx, impl IntToken>) {
+        unsafe { dma_tx.set_paddr(self.periph.i2c_txdr.as_mut_ptr()) };
+    }
+}
+
+#[cfg(any(
+    stm32_mcu = "stm32l4x1",
+    stm32_mcu = "stm32l4x2",
+    stm32_mcu = "stm32l4x3",
+    stm32_mcu = "stm32l4x5",
+    stm32_mcu = "stm32l4x6",
+    stm32_mcu = "stm32l4r5",
+    stm32_mcu = "stm32l4r7",
+    stm32_mcu = "stm32l4r9",
+    stm32_mcu = "stm32l4s5",
+    stm32_mcu = "stm32l4s7",
+    stm32_mcu = "stm32l4s9"
+))]
+impl<T, Ev, Er, Rx> DmaRxReq<Rx> for I2C<T, Ev, Er>
+where
+    T: I2CMap + DmaRxReq<Rx>,
+    Ev: IntToken,
+    Er: IntToken,
+    Rx: DmaChMap,
+{
+    const DMA_RX_REQ: u32 = T::DMA_RX_REQ;
+}
+
+#[cfg(any(
+    stm32_mcu = "stm32l4x1",
+    stm32_mcu = "stm32l4x2",
+    stm32_mcu = "stm32l4x3",
+    stm32_mcu = "stm32l4x5",
+    stm32_mcu = "stm32l4x6",
+    stm32_mcu = "stm32l4r5",
+    stm32_mcu = "stm32l4r7",
+    stm32_mcu = "stm32l4r9",
+    stm32_mcu = "stm32l4s5",
+    stm32_mcu = "stm32l4s7",
+    stm32_mcu = "stm32l4s9"
+))]
+impl<T, Ev, Er, Rx> DmaRxReq<Rx> for I2CEn<T, Ev, Er>
+where
+    T: I2CMap + DmaRxReq<Rx>,
+    Ev: IntToken,
+    Er: IntToken,
+    Rx: DmaChMap,
+{
+    const DMA_RX_REQ: u32 = T::DMA_RX_REQ;
+}
+
+#[cfg(any(
+    stm32_mcu = "stm32l4x1",
+    stm32_mcu = "stm32l4x2",
+    stm32_mcu = "stm32l4x3",
+    stm32_mcu = "stm32l4x5",
+    stm32_mcu = "stm32l4x6",
+    stm32_mcu = "stm32l4r5",
+    stm32_mcu = "stm32l4r7",
+    stm32_mcu = "stm32l4r9",
+    stm32_mcu = "stm32l4s5",
+    stm32_mcu = "stm32l4s7",
+    stm32_mcu = "stm32l4s9"
+))]
+impl<T, Ev, Er, Tx> DmaTxReq<Tx> for I2C<T, Ev, Er>
+where
+    T: I2CMap + DmaTxReq<Tx>,
+    Ev: IntToken,
+    Er: IntToken,
+    Tx: DmaChMap,
+{
+    const DMA_TX_REQ: u32 = T::DMA_TX_REQ;
+}
+
+#[cfg(any(
+    stm32_mcu = "stm32l4x1",
+    stm32_mcu = "stm32l4x2",
+    stm32_mcu = "stm32l4x3",
+    stm32_mcu = "stm32l4x5",
+    stm32_mcu = "stm32l4x6",
+    stm32_mcu = "stm32l4r5",
+    stm32_mcu = "stm32l4r7",
+    stm32_mcu = "stm32l4r9",
+    stm32_mcu = "stm32l4s5",
+    stm32_mcu = "stm32l4s7",
+    stm32_mcu = "stm32l4s9"
+))]
+impl<T, Ev, Er, Tx> DmaTxReq<Tx> for I2CEn<T, Ev, Er>
+where
+    T: I2CMap + DmaTxReq<Tx>,
+    Ev: IntToken,
+    Er: IntToken,
+    Tx: DmaChMap,
+{
+    const DMA_TX_REQ: u32 = T::DMA_TX_REQ;
+}
+
+impl<T: I2CMap, Ev: IntToken, Er: IntToken> DrvRcc for I2C<T, Ev, Er> {
+    #[inline]
+    fn reset(&mut self) {
+        self.0.reset();
+    }
+
+    #[inline]
+    fn disable_stop_mode(&self) {
+        self.0.disable_stop_mode();
+    }
+
+    #[inline]
+    fn enable_stop_mode(&self) {
+        self.0.enable_stop_mode();
+    }
+}
+
+impl<T: I2CMap, Ev: IntToken, Er: IntToken> DrvRcc for I2CEn<T, Ev, Er> {
+    fn reset(&mut self) {
+        self.periph.rcc_busrstr_i2crst.set_bit();
+    }
+
+    fn disable_stop_mode(&self) {
+        self.periph.rcc_bussmenr_i2csmen.clear_bit();
+    }
+
+    fn enable_stop_mode(&self) {
+        self.periph.rcc_bussmenr_i2csmen.set_bit();
+    }
+}
+
+impl<T: I2CMap, Ev: IntToken, Er: IntToken> DrvClockSel for I2C<T, Ev, Er> {
+    #[inline]
+    fn clock_sel(&self, value: u32) {
+        self.0.clock_sel(value);
+    }
+}
+
+impl<T: I2CMap, Ev: IntToken, Er: IntToken> DrvClockSel for I2CEn<T, Ev, Er> {
+    fn clock_sel(&self, value: u32) {
+        self.periph.rcc_ccipr_i2csel.write_bits(value);
+    }
+}
+
+#[cfg(all(
+    feature = "gpio",
+    any(
+        stm32_mcu = "stm32l4x1",
+        stm32_mcu = "stm32l4x2",
+        stm32_mcu = "stm32l4x3",
+        stm32_mcu = "stm32l4x5",
+        stm32_mcu = "stm32l4x6",
+        stm32_mcu = "stm32l4r5",
+        stm32_mcu = "stm32l4r7",
+        stm32_mcu = "stm32l4r9",
+        stm32_mcu = "stm32l4s5",
+        stm32_mcu = "stm32l4s7",
+        stm32_mcu = "stm32l4s9"
+    )
+))]
+mod pins {
+    use super::{I2CSclPin, I2CSdaPin};
+    use crate::gpio::Af4;
+    use drone_stm32_map::periph::{
+        gpio::pin::{
+            GpioA10, GpioA7, GpioA9, GpioB10, GpioB11, GpioB13, GpioB14, GpioB4, GpioB6, GpioB7,
+            GpioB8, GpioB9, GpioC0, GpioC1,
+        },
+        i2c::{I2C1, I2C2, I2C3},
+    };
+    #[cfg(any(
+        stm32_mcu = "stm32l4x6",
+        stm32_mcu = "stm32l4r5",
+        stm32_mcu = "stm32l4r7",
+        stm32_mcu = "stm32l4r9",
+        stm32_mcu = "stm32l4s5",
+        stm32_mcu = "stm32l4s7",
+        stm32_mcu = "stm32l4s9"
+    ))]
+    use drone_stm32_map::periph::{
+        gpio::pin::{GpioD12, GpioD13},
+        i2c::I2C4,
+    };
+
+    pin_map! {
+        I2CSclPin<I2C1> {
+            GpioA9 => Af4,
+            GpioB6 => Af4,
+            GpioB8 => Af4,
+        }
+        I2CSdaPin<I2C1> {
+            GpioA10 => Af4,
+            GpioB7 => Af4,
+            GpioB9 => Af4,
+        }
+        I2CSclPin<I2C2> {
+            GpioB10 => Af4,
+            GpioB13 => Af4,
+        }
+        I2CSdaPin<I2C2> {
+            GpioB11 => Af4,
+            GpioB14 => Af4,
+        }
+        I2CSclPin<I2C3> {
+            GpioA7 => Af4,
+            GpioC0 => Af4,
+        }
+        I2CSdaPin<I2C3> {
+            GpioB4 => Af4,
+            GpioC1 => Af4,
+        }
+    }
+
+    #[cfg(any(
+        stm32_mcu = "stm32l4x6",
+        stm32_mcu = "stm32l4r5",
+        stm32_mcu = "stm32l4r7",
+        stm32_mcu = "stm32l4r9",
+        stm32_mcu = "stm32l4s5",
+        stm32_mcu = "stm32l4s7",
+        stm32_mcu = "stm32l4s9"
+    ))]
+    pin_map! {
+        I2CSclPin<I2C4> {
+            GpioD12 => Af4,
+        }
+        I2CSdaPin<I2C4> {
+            GpioD13 => Af4,
+        }
+    }
+}
+
+#[cfg(any(
+    stm32_mcu = "stm32l4x1",
+    stm32_mcu = "stm32l4x2",
+    stm32_mcu = "stm32l4x3",
+    stm32_mcu = "stm32l4x5",
+    stm32_mcu = "stm32l4x6"
+))]
+mod dma_req {
+    use crate::common::{DmaRxReq, DmaTxReq};
+    use drone_stm32_map::periph::{
+        dma::ch::{Dma1Ch2, Dma1Ch3, Dma1Ch4, Dma1Ch5, Dma1Ch6, Dma1Ch7, Dma2Ch6, Dma2Ch7},
+        i2c::{I2C1, I2C2, I2C3},
+    };
+    #[cfg(stm32_mcu = "stm32l4x6")]
+    use drone_stm32_map::periph::{
+        dma::ch::{Dma2Ch1, Dma2Ch2},
+        i2c::I2C4,
+    };
+
+    impl DmaTxReq<Dma1Ch6> for I2C1 {
+        const DMA_TX_REQ: u32 = 3;
+    }
+
+    impl DmaRxReq<Dma1Ch7> for I2C1 {
+        const DMA_RX_REQ: u32 = 3;
+    }
+
+    impl DmaRxReq<Dma2Ch6> for I2C1 {
+        const DMA_RX_REQ: u32 = 5;
+    }
+
+    impl DmaTxReq<Dma2Ch7> for I2C1 {
+        const DMA_TX_REQ: u32 = 5;
+    }
+
+    impl DmaTxReq<Dma1Ch4> for I2C2 {
+        const DMA_TX_REQ: u32 = 3;
+    }
+
+    impl DmaRxReq<Dma1Ch5> for I2C2 {
+        const DMA_RX_REQ: u32 = 3;
+    }
+
+    impl DmaTxReq<Dma1Ch2> for I2C3 {
+        const DMA_TX_REQ: u32 = 3;
+    }
+
+    impl DmaRxReq<Dma1Ch3> for I2C3 {
+        const DMA_RX_REQ: u32 = 3;
+    }
+
+    #[cfg(stm32_mcu = "stm32l4x6")]
+    impl DmaRxReq<Dma2Ch1> for I2C4 {
+        const DMA_RX_REQ: u32 = 0;
+    }
+
+    #[cfg(stm32_mcu = "stm32l4x6")]
+    impl DmaTxReq<Dma2Ch2> for I2C4 {
+        const DMA_TX_REQ: u32 = 0;
+    }
+}
+
+#[cfg(any(
+    stm32_mcu = "stm32l4r5",
+    stm32_mcu = "stm32l4r7",
+    stm32_mcu = "stm32l4r9",
+    stm32_mcu = "stm32l4s5",
+    stm32_mcu = "stm32l4s7",
+    stm32_mcu = "stm32l4s9"
+))]
+mod dma_req {
+    use crate::common::{DmaRxReq, DmaTxReq};
+    use drone_stm32_map::periph::{
+        dma::ch::DmaChMap,
+        i2c::{I2C1, I2C2, I2C3, I2C4},
+    };
+
+    impl<Rx: DmaChMap> DmaRxReq<Rx> for I2C1 {
+        const DMA_RX_REQ: u32 = 16;
+    }
+
+    impl<Tx: DmaChMap> DmaTxReq<Tx> for I2C1 {
+        const DMA_TX_REQ: u32 = 17;
+    }
+
+    impl<Rx: DmaChMap> DmaRxReq<Rx> for I2C2 {
+        const DMA_RX_REQ: u32 = 18;
+    }
+
+    impl<Tx: DmaChMap> DmaTxReq<Tx> for I2C2 {
+        const DMA_TX_REQ: u32 = 19;
+    }
+
+    impl<Rx: DmaChMap> DmaRxReq<Rx> for I2C3 {
+        const DMA_RX_REQ: u32 = 20;
+    }
+
+    impl<Tx: DmaChMap> DmaTxReq<Tx> for I2C3 {
+        const DMA_TX_REQ: u32 = 21;
+    }
+
+    impl<Rx: DmaChMap> DmaRxReq<Rx> for I2C4 {
+        const DMA_RX_REQ: u32 = 22;
+    }
+
+    impl<Tx: DmaChMap> DmaTxReq<Tx> for I2C4 {
+        const DMA_TX_REQ: u32 = 23;
+    }
+}
+
+impl From<u8> for I2CAddr {
+    fn from(addr: u8) -> Self {
+        Self::Bits7(addr)
+    }
+}
+
+impl From<DmaTransferError> for I2CDmaError {
+    fn from(err: DmaTransferError) -> Self {
+        Self::Dma(err)
+    }
+}
+
+impl From<I2CBreak> for I2CDmaError {
+    fn from(err: I2CBreak) -> Self {
+        Self::I2CBreak(err)
+    }
+}
+
+impl From<I2CError> for I2CDmaError {
+    fn from(err: I2CError) -> Self {
+        Self::I2CError(err)
+    }
+}
+
+impl fmt::Display for I2CDmaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Dma(err) => write!(f, "DMA error: {}", err),
+            Self::I2CBreak(err) => write!(f, "I2C failure: {}", err),
+            Self::I2CError(err) => write!(f, "I2C error: {}", err),
+        }
+    }
+}
+
+impl fmt::Display for I2CError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Berr => write!(f, "I2C bus error."),
+            Self::Ovr => write!(f, "I2C overrun."),
+            Self::Arlo => write!(f, "I2C arbitration lost."),
+            Self::Timeout => write!(f, "I2C timeout."),
+            Self::Alert => write!(f, "I2C SMBus alert."),
+            Self::Pecerr => write!(f, "I2C PEC error."),
+        }
+    }
+}
+
+impl fmt::Display for I2CBreak {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Nack => write!(f, "I2C NACK received."),
+            Self::Stop => write!(f, "I2C STOP received."),
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use super::{I2CDmaError, I2CEn, I2CError, I2CIntError};
+#[cfg(all(feature = "gpio", feature = "tim"))]
+use super::{I2CSclPin, I2CSdaPin};
+#[cfg(all(feature = "gpio", feature = "tim"))]
+use crate::{
+    gpio::{Alternate, GpioPin, OpenDrain},
+    soft_i2c::{SoftI2C, SoftI2CError},
+};
+#[cfg(all(feature = "gpio", feature = "tim"))]
+use drone_cortex_m::drv::timer::Timer;
+use drone_cortex_m::{reg::prelude::*, thr::prelude::*};
+use drone_stm32_map::periph::i2c::{traits::*, I2CMap};
+use futures::prelude::*;
+
+/// Number of SCL periods a STOP condition is waited for in
+/// [`I2CEn::recover`]: the rest of a byte with its acknowledge bit, and the
+/// STOP itself.
+const STOP_SCL_PERIODS: u32 = 10;
+
+/// Upper bound of `BUSY` flag polls per I2C kernel clock cycle. A poll lasts
+/// at least one APB clock cycle, and the kernel clock is either the APB clock,
+/// the system clock, or HSI16, which is at most 8 times slower than the APB
+/// clock.
+const POLLS_PER_KERNEL_CYCLE: u32 = 8;
+
+/// I2C error, which can leave the peripheral or the bus stuck.
+pub trait I2CRecoverable {
+    /// Returns `true` if the error requires a bus recovery.
+    fn needs_recovery(&self) -> bool;
+}
+
+impl<T: I2CMap, Ev: IntToken, Er: IntToken> I2CEn<T, Ev, Er> {
+    /// Resets the peripheral state after an error.
+    ///
+    /// A STOP condition is issued if the bus is busy, and waited for up to ten
+    /// SCL periods, as configured in `I2C_TIMINGR`. Then the peripheral is
+    /// disabled and re-enabled with the previous `I2C_CR1` and `I2C_TIMINGR`
+    /// configuration.
+    pub fn recover(&self) {
+        // The reset clears BUSY, so the STOP is issued before it.
+        self.stop_on_error();
+        let i2c_timingr_val = self.periph.i2c_timingr.load_val();
+        let timingr = &self.periph.i2c_timingr;
+        // SCL period in I2C kernel clock cycles.
+        let scl_period = (timingr.presc().read(&i2c_timingr_val) + 1)
+            * (timingr.scll().read(&i2c_timingr_val) + timingr.sclh().read(&i2c_timingr_val) + 2);
+        for _ in 0..scl_period * STOP_SCL_PERIODS * POLLS_PER_KERNEL_CYCLE {
+            if !self.periph.i2c_isr.busy().read_bit_band() {
+                break;
+            }
+        }
+        let mut i2c_cr1_val = self.periph.i2c_cr1.load_val();
+        self.periph.i2c_cr1.pe().clear_bit();
+        // PE must be kept low for at least 3 APB clock cycles. An APB register
+        // access lasts at least one APB clock cycle, so PE is read back three
+        // times.
+        for _ in 0..3 {
+            while self.periph.i2c_cr1.pe().read_bit() {}
+        }
+        self.periph.i2c_timingr.store_val(i2c_timingr_val);
+        self.periph.i2c_cr1.pe().set(&mut i2c_cr1_val);
+        self.periph.i2c_cr1.store_val(i2c_cr1_val);
+    }
+
+    /// Clears the bus with nine SCL pulses on `scl` and `sda`, and resets the
+    /// peripheral state with [`recover`](I2CEn::recover).
+    ///
+    /// The pins are driven by [`SoftI2C`] with the SCL half period of
+    /// `half_period` `timer` ticks. The returned future gives back the pins in
+    /// the alternate function mode and the timer.
+    #[cfg(all(feature = "gpio", feature = "tim"))]
+    pub fn recover_bus<'a, Scl, Sda, Tm>(
+        &'a self,
+        scl: GpioPin<Scl, Alternate<Scl::Af, OpenDrain>>,
+        sda: GpioPin<Sda, Alternate<Sda::Af, OpenDrain>>,
+        timer: Tm,
+        half_period: u32,
+    ) -> impl Future<
+        Output = (
+            GpioPin<Scl, Alternate<Scl::Af, OpenDrain>>,
+            GpioPin<Sda, Alternate<Sda::Af, OpenDrain>>,
+            Tm,
+            Result<(), SoftI2CError>,
+        ),
+    > + 'a
+    where
+        Scl: I2CSclPin<T>,
+        Sda: I2CSdaPin<T>,
+        Tm: Timer + 'a,
+    {
+        self.recover_bus_impl(scl, sda, timer, half_period)
+    }
+
+    /// Runs the transfer returned by `f`. If the transfer fails with an error,
+    /// which [needs recovery](I2CRecoverable::needs_recovery), the peripheral
+    /// is recovered with [`recover`](I2CEn::recover) and the transfer is
+    /// retried up to `retries` times.
+    pub fn with_recovery<'a, F, Fut, O, E>(
+        &'a self,
+        retries: usize,
+        mut f: F,
+    ) -> impl Future<Output = Result<O, E>> + 'a
+    where
+        F: FnMut() -> Fut + 'a,
+        Fut: Future<Output = Result<O, E>> + 'a,
+        E: I2CRecoverable,
+    {
+        async move {
+            let mut retries = retries;
+            loop {
+                match f().await {
+                    Err(err) if retries > 0 && err.needs_recovery() => {
+                        retries -= 1;
+                        self.recover();
+                    }
+                    result => return result,
+                }
+            }
+        }
+    }
+
+    /// Runs the transfer returned by `f` with the owned `state`, such as DMA
+    /// channels and buffers. The transfer gives back the state along with the
+    /// result. If the transfer fails with an error, which [needs
+    /// recovery](I2CRecoverable::needs_recovery), the peripheral is recovered
+    /// with [`recover`](I2CEn::recover) and the transfer is retried with the
+    /// given back state up to `retries` times.
+    pub fn with_recovery_owned<'a, S, F, Fut, O, E>(
+        &'a self,
+        retries: usize,
+        state: S,
+        mut f: F,
+    ) -> impl Future<Output = (S, Result<O, E>)> + 'a
+    where
+        S: 'a,
+        F: FnMut(S) -> Fut + 'a,
+        Fut: Future<Output = (S, Result<O, E>)> + 'a,
+        E: I2CRecoverable,
+    {
+        async move {
+            let mut retries = retries;
+            let mut state = state;
+            loop {
+                match f(state).await {
+                    (next, Err(err)) if retries > 0 && err.needs_recovery() => {
+                        retries -= 1;
+                        state = next;
+                        self.recover();
+                    }
+                    output => return output,
+                }
+            }
+        }
+    }
+
+    #[cfg(all(feature = "gpio", feature = "tim"))]
+    async fn recover_bus_impl<Scl, Sda, Tm>(
+        &self,
+        scl: GpioPin<Scl, Alternate<Scl::Af, OpenDrain>>,
+        sda: GpioPin<Sda, Alternate<Sda::Af, OpenDrain>>,
+        timer: Tm,
+        half_period: u32,
+    ) -> (
+        GpioPin<Scl, Alternate<Scl::Af, OpenDrain>>,
+        GpioPin<Sda, Alternate<Sda::Af, OpenDrain>>,
+        Tm,
+        Result<(), SoftI2CError>,
+    )
+    where
+        Scl: I2CSclPin<T>,
+        Sda: I2CSdaPin<T>,
+        Tm: Timer,
+    {
+        self.periph.i2c_cr1.pe().clear_bit();
+        let mut soft_i2c = SoftI2C::new(
+            scl.into_open_drain_output(),
+            sda.into_open_drain_output(),
+            timer,
+            half_period,
+        );
+        let result = soft_i2c.clear_bus().await;
+        let (scl, sda, timer) = soft_i2c.free();
+        let (scl, sda) = self.setup_pins(scl, sda);
+        self.recover();
+        (scl, sda, timer, result)
+    }
+}
+
+impl I2CRecoverable for I2CError {
+    fn needs_recovery(&self) -> bool {
+        match self {
+            Self::Berr | Self::Arlo | Self::Timeout => true,
+            Self::Ovr | Self::Alert | Self::Pecerr => false,
+        }
+    }
+}
+
+impl I2CRecoverable for I2CDmaError {
+    fn needs_recovery(&self) -> bool {
+        match self {
+            Self::I2CError(err) => err.needs_recovery(),
+            Self::Dma(_) | Self::I2CBreak(_) => false,
+        }
+    }
+}
+
+impl I2CRecoverable for I2CIntError {
+    fn needs_recovery(&self) -> bool {
+        match self {
+            Self::I2CError(err) => err.needs_recovery(),
+            Self::I2CBreak(_) | Self::BlockCount => false,
+        }
+    }
+}
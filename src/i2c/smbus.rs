@@ -0,0 +1,367 @@
+use super::{I2CEn, I2CIntError, IntBuf};
+use core::{fmt, num::NonZeroUsize};
+use drone_cortex_m::{fib, reg::prelude::*, thr::prelude::*};
+use drone_stm32_map::periph::i2c::{traits::*, I2CMap};
+use futures::prelude::*;
+
+/// SMBus Alert Response Address.
+const ALERT_RESPONSE_ADDR: u8 = 0x0C;
+/// Maximum number of data bytes in a block transfer.
+const BLOCK_MAX: usize = 32;
+
+/// SMBus host driver.
+///
+/// Created with [`I2CEn::smbus`].
+pub struct SmBus<'a, T: I2CMap, Ev: IntToken, Er: IntToken> {
+    i2c: &'a I2CEn<T, Ev, Er>,
+    pec: bool,
+}
+
+/// SMBus timeout configuration.
+#[derive(Clone, Copy, Debug)]
+pub struct SmBusTimeoutConfig {
+    /// I2C kernel clock frequency in Hz, as selected with
+    /// [`DrvClockSel::clock_sel`](crate::common::DrvClockSel::clock_sel).
+    pub kernel_clock: u32,
+    /// SCL low timeout (`TIMEOUTA`) in microseconds. SMBus specifies 25 ms.
+    pub clock_low: Option<u32>,
+    /// Cumulative clock low extension timeout (`TIMEOUTB`) in microseconds.
+    /// SMBus specifies 10 ms for a host.
+    pub clock_ext: Option<u32>,
+}
+
+/// Error returned when a timeout doesn't fit in the `I2C_TIMEOUTR` fields.
+#[derive(Debug)]
+pub struct SmBusTimeoutError;
+
+impl<T: I2CMap, Ev: IntToken, Er: IntToken> I2CEn<T, Ev, Er> {
+    /// Returns an SMBus host driver. If `pec` is set, the packet error
+    /// checking is enabled, and PEC bytes are generated and checked by the
+    /// hardware for every command.
+    pub fn smbus(&self, pec: bool) -> SmBus<'_, T, Ev, Er> {
+        self.periph.i2c_cr1.modify(|r| {
+            if pec {
+                self.periph.i2c_cr1.pecen().set(r);
+            } else {
+                self.periph.i2c_cr1.pecen().clear(r);
+            }
+        });
+        SmBus { i2c: self, pec }
+    }
+
+    /// Configures the SMBus timeouts. A timeout is reported as
+    /// [`I2CError::Timeout`](super::I2CError::Timeout).
+    pub fn set_smbus_timeout(&self, config: &SmBusTimeoutConfig) -> Result<(), SmBusTimeoutError> {
+        let clock_low = config
+            .clock_low
+            .map(|us| timeout_bits(config.kernel_clock, us))
+            .transpose()?;
+        let clock_ext = config
+            .clock_ext
+            .map(|us| timeout_bits(config.kernel_clock, us))
+            .transpose()?;
+        self.periph.i2c_timeoutr.reset();
+        self.periph.i2c_timeoutr.store_val({
+            let mut val = self.periph.i2c_timeoutr.default_val();
+            if let Some(bits) = clock_low {
+                self.periph.i2c_timeoutr.timeouta().write(&mut val, bits);
+                self.periph.i2c_timeoutr.timouten().set(&mut val);
+            }
+            if let Some(bits) = clock_ext {
+                self.periph.i2c_timeoutr.timeoutb().write(&mut val, bits);
+                self.periph.i2c_timeoutr.texten().set(&mut val);
+            }
+            val
+        });
+        Ok(())
+    }
+
+    async fn smbus_write(&self, addr: u8, bytes: &[u8], pec: bool) -> Result<(), I2CIntError> {
+        let buf = IntBuf {
+            addr: bytes.as_ptr() as usize,
+            len: bytes.len(),
+        };
+        let result = self
+            .int_tx(
+                buf,
+                addr.into(),
+                self.session_cr1_val(),
+                self.pec_cr2_val(self.periph.i2c_cr2.default_val(), pec),
+                true,
+            )
+            .await;
+        if result.is_err() {
+            self.stop_on_error();
+        }
+        result
+    }
+
+    async fn smbus_read(
+        &self,
+        addr: u8,
+        command: &[u8],
+        len: usize,
+        pec: bool,
+        block: bool,
+    ) -> Result<Vec<u8>, I2CIntError> {
+        if !command.is_empty() {
+            let buf = IntBuf {
+                addr: command.as_ptr() as usize,
+                len: command.len(),
+            };
+            let i2c_cr2_val = self.periph.i2c_cr2.default_val();
+            let result = self
+                .int_tx(buf, addr.into(), self.session_cr1_val(), i2c_cr2_val, false)
+                .await;
+            if let Err(err) = result {
+                self.stop_on_error();
+                return Err(err);
+            }
+        }
+        let i2c_cr2_val = self.periph.i2c_cr2.default_val();
+        let mut data = Vec::new();
+        data.resize(len, 0);
+        let buf = IntBuf {
+            addr: data.as_mut_ptr() as usize,
+            len,
+        };
+        let result = self
+            .int_rx(
+                buf,
+                addr.into(),
+                self.session_cr1_val(),
+                self.pec_cr2_val(i2c_cr2_val, pec),
+                true,
+                block,
+            )
+            .await;
+        match result {
+            Ok(count) => {
+                data.truncate(count);
+                Ok(data)
+            }
+            Err(err) => {
+                self.stop_on_error();
+                Err(err)
+            }
+        }
+    }
+
+    fn pec_cr2_val(&self, mut val: T::I2CCr2Val, pec: bool) -> T::I2CCr2Val {
+        if pec {
+            self.periph.i2c_cr2.pecbyte().set(&mut val);
+        }
+        val
+    }
+
+    fn alert_stream(&self) -> impl Stream<Item = NonZeroUsize> {
+        let alert = *self.periph.i2c_isr.alert();
+        let alertcf = *self.periph.i2c_icr.alertcf();
+        self.int_er.add_stream_pulse_skip(fib::new_fn(move || {
+            if alert.read_bit_band() {
+                alertcf.set_bit_band();
+                fib::Yielded(Some(1))
+            } else {
+                fib::Yielded(None)
+            }
+        }))
+    }
+}
+
+impl<'a, T: I2CMap, Ev: IntToken, Er: IntToken> SmBus<'a, T, Ev, Er> {
+    /// Performs the Quick Command. The command is the direction bit itself:
+    /// `read` selects a read, otherwise a write.
+    pub fn quick_command(
+        &self,
+        addr: u8,
+        read: bool,
+    ) -> impl Future<Output = Result<(), I2CIntError>> + 'a {
+        let i2c = self.i2c;
+        async move {
+            if read {
+                i2c.smbus_read(addr, &[], 0, false, false).await.map(drop)
+            } else {
+                i2c.smbus_write(addr, &[], false).await
+            }
+        }
+    }
+
+    /// Performs the Send Byte protocol.
+    pub fn send_byte(
+        &self,
+        addr: u8,
+        byte: u8,
+    ) -> impl Future<Output = Result<(), I2CIntError>> + 'a {
+        let (i2c, pec) = (self.i2c, self.pec);
+        async move { i2c.smbus_write(addr, &[byte], pec).await }
+    }
+
+    /// Performs the Receive Byte protocol.
+    pub fn receive_byte(&self, addr: u8) -> impl Future<Output = Result<u8, I2CIntError>> + 'a {
+        let (i2c, pec) = (self.i2c, self.pec);
+        async move {
+            i2c.smbus_read(addr, &[], 1, pec, false)
+                .await
+                .map(|data| data[0])
+        }
+    }
+
+    /// Performs the Write Byte protocol.
+    pub fn write_byte(
+        &self,
+        addr: u8,
+        command: u8,
+        byte: u8,
+    ) -> impl Future<Output = Result<(), I2CIntError>> + 'a {
+        let (i2c, pec) = (self.i2c, self.pec);
+        async move { i2c.smbus_write(addr, &[command, byte], pec).await }
+    }
+
+    /// Performs the Write Word protocol. The word is transmitted low byte
+    /// first.
+    pub fn write_word(
+        &self,
+        addr: u8,
+        command: u8,
+        word: u16,
+    ) -> impl Future<Output = Result<(), I2CIntError>> + 'a {
+        let (i2c, pec) = (self.i2c, self.pec);
+        let [low, high] = word.to_le_bytes();
+        async move { i2c.smbus_write(addr, &[command, low, high], pec).await }
+    }
+
+    /// Performs the Read Byte protocol.
+    pub fn read_byte(
+        &self,
+        addr: u8,
+        command: u8,
+    ) -> impl Future<Output = Result<u8, I2CIntError>> + 'a {
+        let (i2c, pec) = (self.i2c, self.pec);
+        async move {
+            i2c.smbus_read(addr, &[command], 1, pec, false)
+                .await
+                .map(|data| data[0])
+        }
+    }
+
+    /// Performs the Read Word protocol. The word is received low byte first.
+    pub fn read_word(
+        &self,
+        addr: u8,
+        command: u8,
+    ) -> impl Future<Output = Result<u16, I2CIntError>> + 'a {
+        let (i2c, pec) = (self.i2c, self.pec);
+        async move {
+            i2c.smbus_read(addr, &[command], 2, pec, false)
+                .await
+                .map(|data| u16::from_le_bytes([data[0], data[1]]))
+        }
+    }
+
+    /// Performs the Process Call protocol. Writes `word` and reads the reply
+    /// word after a repeated START.
+    pub fn process_call(
+        &self,
+        addr: u8,
+        command: u8,
+        word: u16,
+    ) -> impl Future<Output = Result<u16, I2CIntError>> + 'a {
+        let (i2c, pec) = (self.i2c, self.pec);
+        let [low, high] = word.to_le_bytes();
+        async move {
+            i2c.smbus_read(addr, &[command, low, high], 2, pec, false)
+                .await
+                .map(|data| u16::from_le_bytes([data[0], data[1]]))
+        }
+    }
+
+    /// Performs the Block Write protocol. The byte count is transmitted
+    /// before `data`.
+    ///
+    /// # Panics
+    ///
+    /// If `data` is longer than 32 bytes.
+    pub fn block_write(
+        &self,
+        addr: u8,
+        command: u8,
+        data: &[u8],
+    ) -> impl Future<Output = Result<(), I2CIntError>> + 'a {
+        if data.len() > BLOCK_MAX {
+            panic!("SMBus block is too long");
+        }
+        let (i2c, pec) = (self.i2c, self.pec);
+        let mut bytes = Vec::with_capacity(data.len() + 2);
+        bytes.push(command);
+        bytes.push(data.len() as u8);
+        bytes.extend_from_slice(data);
+        async move { i2c.smbus_write(addr, &bytes, pec).await }
+    }
+
+    /// Performs the Block Read protocol. The number of the received bytes is
+    /// given by the device.
+    pub fn block_read(
+        &self,
+        addr: u8,
+        command: u8,
+    ) -> impl Future<Output = Result<Vec<u8>, I2CIntError>> + 'a {
+        let (i2c, pec) = (self.i2c, self.pec);
+        async move {
+            i2c.smbus_read(addr, &[command], BLOCK_MAX + 1, pec, true)
+                .await
+                .map(|mut data| {
+                    // Drop the byte count.
+                    data.remove(0);
+                    data
+                })
+        }
+    }
+
+    /// Enables the SMBALERT# input and returns a stream of addresses of the
+    /// devices signaling an alert.
+    ///
+    /// For each alert the Alert Response Address is read. Alerts, which are
+    /// signaled before the previous one is serviced, are coalesced. An alert
+    /// signaled during another transfer fails that transfer with
+    /// [`I2CError::Alert`](super::I2CError::Alert) instead.
+    pub fn alerts(&self) -> impl Stream<Item = Result<u8, I2CIntError>> + 'a {
+        let (i2c, pec) = (self.i2c, self.pec);
+        let stream = i2c.alert_stream();
+        i2c.periph.i2c_cr1.modify(|r| {
+            i2c.periph.i2c_cr1.errie().set(r);
+            i2c.periph.i2c_cr1.smbhen().set(r);
+            i2c.periph.i2c_cr1.alerten().set(r);
+        });
+        stream.then(move |_| {
+            async move {
+                i2c.smbus_read(ALERT_RESPONSE_ADDR, &[], 1, pec, false)
+                    .await
+                    .map(|data| data[0] >> 1)
+            }
+        })
+    }
+
+    /// Disables the SMBALERT# input.
+    pub fn disable_alerts(&self) {
+        self.i2c.periph.i2c_cr1.modify(|r| {
+            self.i2c.periph.i2c_cr1.alerten().clear(r);
+            self.i2c.periph.i2c_cr1.smbhen().clear(r);
+        });
+    }
+}
+
+impl fmt::Display for SmBusTimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SMBus timeout is out of range.")
+    }
+}
+
+/// Converts `us` microseconds to a `TIMEOUTA` or `TIMEOUTB` value, which
+/// count in 2048 kernel clock periods.
+fn timeout_bits(kernel_clock: u32, us: u32) -> Result<u32, SmBusTimeoutError> {
+    let ticks = u64::from(kernel_clock) * u64::from(us) / (2048 * 1_000_000);
+    match ticks.checked_sub(1) {
+        Some(bits) if bits <= 0xFFF => Ok(bits as u32),
+        _ => Err(SmBusTimeoutError),
+    }
+}
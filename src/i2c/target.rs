@@ -0,0 +1,423 @@
+use super::{I2CAddr, I2CDmaError, I2CEn, I2CError};
+use crate::dma::{DmaChEn, DmaDir, DmaReadBuffer, DmaTransferError, DmaWriteBuffer};
+use core::fmt;
+use drone_cortex_m::{fib, reg::prelude::*, thr::prelude::*};
+use drone_stm32_map::periph::{
+    dma::ch::DmaChMap,
+    i2c::{traits::*, I2CMap},
+};
+use futures::{
+    future::{self, Either},
+    prelude::*,
+};
+
+/// I2C target mode configuration.
+#[derive(Clone, Copy, Debug)]
+pub struct I2CTargetConfig {
+    /// Own address 1.
+    pub addr1: I2CAddr,
+    /// Own address 2. Only 7-bit addresses are supported.
+    pub addr2: Option<u8>,
+    /// Number of least significant bits of the own address 2, which are
+    /// ignored in comparison, from 0 to 7.
+    pub addr2_mask: u8,
+}
+
+/// I2C address match event in the target mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct I2CAddrMatch {
+    /// Matched address. For 10-bit addresses contains the two most significant
+    /// bits of the address in the header format.
+    pub addr: u8,
+    /// Transfer direction.
+    pub dir: I2CTargetDir,
+}
+
+/// I2C transfer direction from the target point of view.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum I2CTargetDir {
+    /// The controller writes, the target receives.
+    Receive,
+    /// The controller reads, the target transmits.
+    Transmit,
+}
+
+/// Error returned when address match events are not consumed in time.
+#[derive(Debug)]
+pub struct I2CAddrOverflow;
+
+impl<T: I2CMap, Ev: IntToken, Er: IntToken> I2CEn<T, Ev, Er> {
+    /// Starts listening on the own addresses from `config` in the target
+    /// mode.
+    ///
+    /// Returns a stream of address match events. `capacity` is the number of
+    /// events, which can be buffered before [`I2CAddrOverflow`] is reported.
+    /// The address is acknowledged right away. Afterwards the clock is
+    /// stretched when a received byte can't be stored or a byte to transmit
+    /// isn't provided, until the transfer is serviced by one of the `target_*`
+    /// methods. The peripheral is disabled for a moment to configure the clock
+    /// stretching.
+    ///
+    /// # Panics
+    ///
+    /// If `config.addr2_mask` is greater than 7.
+    pub fn listen(
+        &self,
+        config: &I2CTargetConfig,
+        capacity: usize,
+    ) -> impl Stream<Item = Result<I2CAddrMatch, I2CAddrOverflow>> {
+        if config.addr2_mask > 7 {
+            panic!("I2C own address 2 mask is out of range");
+        }
+        self.periph.i2c_oar1.oa1en().clear_bit();
+        self.periph.i2c_oar2.oa2en().clear_bit();
+        self.periph.i2c_oar1.store_val({
+            let mut val = self.periph.i2c_oar1.default_val();
+            match config.addr1 {
+                I2CAddr::Bits7(addr) => {
+                    self.periph.i2c_oar1.oa1mode().clear(&mut val);
+                    self.periph
+                        .i2c_oar1
+                        .oa1()
+                        .write(&mut val, u32::from(addr) << 1);
+                }
+                I2CAddr::Bits10(addr) => {
+                    self.periph.i2c_oar1.oa1mode().set(&mut val);
+                    self.periph.i2c_oar1.oa1().write(&mut val, u32::from(addr));
+                }
+            }
+            self.periph.i2c_oar1.oa1en().set(&mut val);
+            val
+        });
+        if let Some(addr) = config.addr2 {
+            self.periph.i2c_oar2.store_val({
+                let mut val = self.periph.i2c_oar2.default_val();
+                self.periph.i2c_oar2.oa2().write(&mut val, u32::from(addr));
+                self.periph
+                    .i2c_oar2
+                    .oa2msk()
+                    .write(&mut val, u32::from(config.addr2_mask));
+                self.periph.i2c_oar2.oa2en().set(&mut val);
+                val
+            });
+        }
+        let stream = self.addr_match_stream(capacity);
+        // NOSTRETCH can be changed only while the peripheral is disabled.
+        self.periph.i2c_cr1.pe().clear_bit();
+        self.periph.i2c_cr1.modify(|r| {
+            self.periph.i2c_cr1.nostretch().clear(r);
+            self.periph.i2c_cr1.addrie().set(r);
+            self.periph.i2c_cr1.pe().set(r);
+        });
+        stream
+    }
+
+    /// Stops listening on the own addresses.
+    pub fn unlisten(&self) {
+        self.periph.i2c_cr1.modify(|r| {
+            self.periph.i2c_cr1.addrie().clear(r);
+        });
+        self.periph.i2c_oar1.oa1en().clear_bit();
+        self.periph.i2c_oar2.oa2en().clear_bit();
+    }
+
+    /// Receives bytes written by the controller to `buf` using DMA.
+    ///
+    /// Should be called after an [`I2CTargetDir::Receive`] address match. The
+    /// returned future resolves on STOP or repeated START condition, and gives
+    /// back the DMA channel, the buffer, and the number of received bytes.
+    /// Bytes beyond the length of `buf` are discarded.
+    pub fn target_rx<'a, Rx: DmaChMap, RxI: IntToken, B: DmaWriteBuffer<Word = u8>>(
+        &'a self,
+        dma_rx: DmaChEn<Rx, RxI>,
+        buf: B,
+    ) -> impl Future<Output = (DmaChEn<Rx, RxI>, B, Result<usize, I2CDmaError>)> + 'a {
+        self.target_rx_impl(dma_rx, buf)
+    }
+
+    /// Transmits bytes from `buf` to the controller using DMA.
+    ///
+    /// Should be called after an [`I2CTargetDir::Transmit`] address match. The
+    /// returned future resolves on STOP or repeated START condition, and gives
+    /// back the DMA channel, the buffer, and the number of bytes passed to the
+    /// peripheral. `0xFF` is transmitted if the controller reads beyond the
+    /// length of `buf`.
+    pub fn target_tx<'a, Tx: DmaChMap, TxI: IntToken, B: DmaReadBuffer<Word = u8>>(
+        &'a self,
+        dma_tx: DmaChEn<Tx, TxI>,
+        buf: B,
+    ) -> impl Future<Output = (DmaChEn<Tx, TxI>, B, Result<usize, I2CDmaError>)> + 'a {
+        self.target_tx_impl(dma_tx, buf)
+    }
+
+    /// Receives bytes written by the controller to `buf` from the interrupt.
+    ///
+    /// Should be called after an [`I2CTargetDir::Receive`] address match. The
+    /// returned future resolves on STOP or repeated START condition, and gives
+    /// back the buffer and the number of received bytes. Bytes beyond the
+    /// length of `buf` are discarded.
+    pub fn target_rx_int<'a, B: DmaWriteBuffer<Word = u8>>(
+        &'a self,
+        buf: B,
+    ) -> impl Future<Output = (B, Result<usize, I2CError>)> + 'a {
+        self.target_rx_int_impl(buf)
+    }
+
+    /// Transmits bytes from `buf` to the controller from the interrupt.
+    ///
+    /// Should be called after an [`I2CTargetDir::Transmit`] address match. The
+    /// returned future resolves on STOP or repeated START condition, and gives
+    /// back the buffer and the number of bytes passed to the peripheral. The
+    /// last of them is discarded if the controller stops reading. `0xFF` is
+    /// transmitted if the controller reads beyond the length of `buf`.
+    pub fn target_tx_int<'a, B: DmaReadBuffer<Word = u8>>(
+        &'a self,
+        buf: B,
+    ) -> impl Future<Output = (B, Result<usize, I2CError>)> + 'a {
+        self.target_tx_int_impl(buf)
+    }
+
+    async fn target_rx_impl<Rx: DmaChMap, RxI: IntToken, B: DmaWriteBuffer<Word = u8>>(
+        &self,
+        dma_rx: DmaChEn<Rx, RxI>,
+        buf: B,
+    ) -> (DmaChEn<Rx, RxI>, B, Result<usize, I2CDmaError>) {
+        let rx_ccr = dma_rx.config().minc(true).teie(true).tcie(true).build();
+        let mut dma_rx = dma_rx.start_rx(buf, rx_ccr);
+        let end = self.target_dma_end(dma_rx.transfer_complete());
+        self.periph.i2c_cr1.modify(|r| {
+            self.periph.i2c_cr1.rxdmaen().set(r);
+        });
+        let result = self.int_session(end).await;
+        self.periph.i2c_cr1.modify(|r| {
+            self.periph.i2c_cr1.rxdmaen().clear(r);
+            self.periph.i2c_cr1.rxie().clear(r);
+        });
+        let (dma_rx, buf, count) = dma_rx.abort();
+        let result = match result {
+            Ok(Ok(())) => Ok(count),
+            Ok(Err(err)) => Err(err.into()),
+            Err(err) => Err(err.into()),
+        };
+        (dma_rx, buf, result)
+    }
+
+    async fn target_tx_impl<Tx: DmaChMap, TxI: IntToken, B: DmaReadBuffer<Word = u8>>(
+        &self,
+        dma_tx: DmaChEn<Tx, TxI>,
+        buf: B,
+    ) -> (DmaChEn<Tx, TxI>, B, Result<usize, I2CDmaError>) {
+        let tx_ccr = dma_tx
+            .config()
+            .dir(DmaDir::MemToPeriph)
+            .minc(true)
+            .teie(true)
+            .tcie(true)
+            .build();
+        let mut dma_tx = dma_tx.start_tx(buf, tx_ccr);
+        let end = self.target_dma_end(dma_tx.transfer_complete());
+        self.periph.i2c_cr1.modify(|r| {
+            self.periph.i2c_cr1.txdmaen().set(r);
+        });
+        let result = self.int_session(end).await;
+        self.periph.i2c_cr1.modify(|r| {
+            self.periph.i2c_cr1.txdmaen().clear(r);
+            self.periph.i2c_cr1.txie().clear(r);
+        });
+        let (dma_tx, buf, count) = dma_tx.abort();
+        let result = match result {
+            Ok(Ok(())) => Ok(count),
+            Ok(Err(err)) => Err(err.into()),
+            Err(err) => Err(err.into()),
+        };
+        (dma_tx, buf, result)
+    }
+
+    async fn target_rx_int_impl<B: DmaWriteBuffer<Word = u8>>(
+        &self,
+        mut buf: B,
+    ) -> (B, Result<usize, I2CError>) {
+        let (addr, len) = buf.dma_write_buffer();
+        let addr = addr as usize;
+        let rxne = *self.periph.i2c_isr.rxne();
+        let stopf = *self.periph.i2c_isr.stopf();
+        let addrf = *self.periph.i2c_isr.addr();
+        let stopcf = *self.periph.i2c_icr.stopcf();
+        let rxdata = *self.periph.i2c_rxdr.rxdata();
+        let mut count = 0;
+        let rx = self.int_ev.add_future(fib::new_fn(move || {
+            if rxne.read_bit_band() {
+                let byte = rxdata.read_bits() as u8;
+                if count < len {
+                    unsafe { *(addr as *mut u8).add(count) = byte };
+                    count += 1;
+                }
+            }
+            if stopf.read_bit_band() {
+                stopcf.set_bit_band();
+                fib::Complete(count)
+            } else if addrf.read_bit_band() {
+                fib::Complete(count)
+            } else {
+                fib::Yielded(())
+            }
+        }));
+        self.periph.i2c_cr1.modify(|r| {
+            self.periph.i2c_cr1.rxie().set(r);
+        });
+        let result = self.int_session(rx).await;
+        self.periph.i2c_cr1.modify(|r| {
+            self.periph.i2c_cr1.rxie().clear(r);
+        });
+        (buf, result)
+    }
+
+    async fn target_tx_int_impl<B: DmaReadBuffer<Word = u8>>(
+        &self,
+        buf: B,
+    ) -> (B, Result<usize, I2CError>) {
+        let (addr, len) = buf.dma_read_buffer();
+        let addr = addr as usize;
+        let txis = *self.periph.i2c_isr.txis();
+        let nackf = *self.periph.i2c_isr.nackf();
+        let stopf = *self.periph.i2c_isr.stopf();
+        let addrf = *self.periph.i2c_isr.addr();
+        let nackcf = *self.periph.i2c_icr.nackcf();
+        let stopcf = *self.periph.i2c_icr.stopcf();
+        let txdata = *self.periph.i2c_txdr.txdata();
+        let mut count = 0;
+        let tx = self.int_ev.add_future(fib::new_fn(move || {
+            if txis.read_bit_band() {
+                if count < len {
+                    let byte = unsafe { *(addr as *const u8).add(count) };
+                    txdata.write_bits(u32::from(byte));
+                    count += 1;
+                } else {
+                    txdata.write_bits(0xFF);
+                }
+            }
+            if nackf.read_bit_band() {
+                nackcf.set_bit_band();
+            }
+            if stopf.read_bit_band() {
+                stopcf.set_bit_band();
+                fib::Complete(count)
+            } else if addrf.read_bit_band() {
+                fib::Complete(count)
+            } else {
+                fib::Yielded(())
+            }
+        }));
+        self.periph.i2c_cr1.modify(|r| {
+            self.periph.i2c_cr1.txie().set(r);
+        });
+        let result = self.int_session(tx).await;
+        self.periph.i2c_cr1.modify(|r| {
+            self.periph.i2c_cr1.txie().clear(r);
+        });
+        (buf, result)
+    }
+
+    /// Returns a future, which resolves on STOP or repeated START condition in
+    /// the target mode of a DMA transfer.
+    ///
+    /// After `dma_complete` resolves, the DMA requests are replaced by the
+    /// interrupt, which discards received bytes and transmits `0xFF`, so the
+    /// bus isn't held when the controller goes beyond the buffer.
+    fn target_dma_end(
+        &self,
+        dma_complete: impl Future<Output = Result<(), DmaTransferError>> + Unpin,
+    ) -> impl Future<Output = Result<(), DmaTransferError>> + Unpin {
+        let cr1 = self.periph.i2c_cr1;
+        future::select(dma_complete, self.target_end()).then(move |output| match output {
+            Either::Left((Ok(()), end)) => {
+                cr1.modify(|r| {
+                    if cr1.rxdmaen().read(r) {
+                        cr1.rxdmaen().clear(r);
+                        cr1.rxie().set(r);
+                    }
+                    if cr1.txdmaen().read(r) {
+                        cr1.txdmaen().clear(r);
+                        cr1.txie().set(r);
+                    }
+                });
+                Either::Left(end.map(Ok))
+            }
+            Either::Left((Err(err), _)) => Either::Right(future::ready(Err(err))),
+            Either::Right(((), _)) => Either::Right(future::ready(Ok(()))),
+        })
+    }
+
+    /// Returns a future, which resolves on STOP or repeated START condition in
+    /// the target mode. Bytes are serviced only in the interrupt fallback of
+    /// [`target_dma_end`](I2CEn::target_dma_end).
+    fn target_end(&self) -> impl Future<Output = ()> {
+        let rxie = *self.periph.i2c_cr1.rxie();
+        let txie = *self.periph.i2c_cr1.txie();
+        let rxne = *self.periph.i2c_isr.rxne();
+        let txis = *self.periph.i2c_isr.txis();
+        let nackf = *self.periph.i2c_isr.nackf();
+        let stopf = *self.periph.i2c_isr.stopf();
+        let addrf = *self.periph.i2c_isr.addr();
+        let nackcf = *self.periph.i2c_icr.nackcf();
+        let stopcf = *self.periph.i2c_icr.stopcf();
+        let rxdata = *self.periph.i2c_rxdr.rxdata();
+        let txdata = *self.periph.i2c_txdr.txdata();
+        self.int_ev.add_future(fib::new_fn(move || {
+            if rxie.read_bit() && rxne.read_bit_band() {
+                rxdata.read_bits();
+            }
+            if txie.read_bit() && txis.read_bit_band() {
+                txdata.write_bits(0xFF);
+            }
+            if nackf.read_bit_band() {
+                nackcf.set_bit_band();
+            }
+            if stopf.read_bit_band() {
+                stopcf.set_bit_band();
+                fib::Complete(())
+            } else if addrf.read_bit_band() {
+                fib::Complete(())
+            } else {
+                fib::Yielded(())
+            }
+        }))
+    }
+
+    fn addr_match_stream(
+        &self,
+        capacity: usize,
+    ) -> impl Stream<Item = Result<I2CAddrMatch, I2CAddrOverflow>> {
+        let addrf = *self.periph.i2c_isr.addr();
+        let dir = *self.periph.i2c_isr.dir();
+        let addcode = *self.periph.i2c_isr.addcode();
+        let txe = *self.periph.i2c_isr.txe();
+        let addrcf = *self.periph.i2c_icr.addrcf();
+        self.int_ev.add_stream_ring(
+            capacity,
+            |_| Err(I2CAddrOverflow),
+            fib::new_fn(move || {
+                if addrf.read_bit_band() {
+                    let dir = if dir.read_bit_band() {
+                        // Flush stale data from the previous transfer.
+                        txe.set_bit_band();
+                        I2CTargetDir::Transmit
+                    } else {
+                        I2CTargetDir::Receive
+                    };
+                    let addr = addcode.read_bits() as u8;
+                    addrcf.set_bit_band();
+                    fib::Yielded(Some(I2CAddrMatch { addr, dir }))
+                } else {
+                    fib::Yielded(None)
+                }
+            }),
+        )
+    }
+}
+
+impl fmt::Display for I2CAddrOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "I2C address match stream overflow.")
+    }
+}
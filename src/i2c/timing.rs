@@ -0,0 +1,262 @@
+//! I2C timing calculation.
+//!
+//! The module doesn't touch the registers, so it can be tested on the host.
+
+use core::fmt;
+
+/// Minimum delay of the analog noise filter in picoseconds.
+const AF_DELAY_MIN: u64 = 50_000;
+/// Maximum delay of the analog noise filter in picoseconds.
+const AF_DELAY_MAX: u64 = 260_000;
+
+/// I2C bus speed mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum I2CSpeed {
+    /// Standard-mode, up to 100 kHz.
+    Standard,
+    /// Fast-mode, up to 400 kHz.
+    Fast,
+    /// Fast-mode Plus, up to 1 MHz.
+    FastPlus,
+}
+
+/// I2C timing calculation input.
+#[derive(Clone, Copy, Debug)]
+pub struct I2CTimingConfig {
+    /// I2C kernel clock frequency in Hz, as selected with
+    /// [`DrvClockSel::clock_sel`](crate::common::DrvClockSel::clock_sel).
+    pub kernel_clock: u32,
+    /// Bus speed mode.
+    pub speed: I2CSpeed,
+    /// SCL and SDA rise time in nanoseconds.
+    pub rise_time: u32,
+    /// SCL and SDA fall time in nanoseconds.
+    pub fall_time: u32,
+    /// Enables the analog noise filter.
+    pub analog_filter: bool,
+    /// Digital noise filter length in kernel clock periods, from 0 to 15.
+    pub digital_filter: u32,
+}
+
+/// `I2C_TIMINGR` field values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct I2CTiming {
+    /// Timing prescaler.
+    pub presc: u32,
+    /// Data setup time.
+    pub scldel: u32,
+    /// Data hold time.
+    pub sdadel: u32,
+    /// SCL high period.
+    pub sclh: u32,
+    /// SCL low period.
+    pub scll: u32,
+}
+
+/// I2C timing calculation error.
+#[derive(Debug)]
+pub enum I2CTimingError {
+    /// Digital noise filter length is greater than 15.
+    DigitalFilter,
+    /// Rise or fall time exceeds the limit of the bus speed mode.
+    RiseFall,
+    /// No data hold time satisfies the bus speed mode with the given rise and
+    /// fall times and noise filters.
+    DataHold,
+    /// The kernel clock is too slow or too fast to reach the bus speed.
+    Clock,
+}
+
+struct Spec {
+    rate: u64,
+    rate_min: u64,
+    hddat_min: u64,
+    vddat_max: u64,
+    sudat_min: u64,
+    low_min: u64,
+    high_min: u64,
+    rise_max: u64,
+    fall_max: u64,
+}
+
+impl I2CTimingConfig {
+    /// Calculates `I2C_TIMINGR` field values.
+    ///
+    /// The smallest prescaler satisfying the bus specification is chosen. The
+    /// SCL high period is kept at its minimum, and the SCL low period is
+    /// stretched to not exceed the bus speed. If the resulting bus speed is
+    /// below 80% of the nominal one, [`I2CTimingError::Clock`] is returned.
+    ///
+    /// The method isn't a `const fn`, because the prescaler search needs loops
+    /// and early returns, which `const fn` doesn't support yet. For a fixed
+    /// configuration the result can be stored as an [`I2CTiming`] literal.
+    pub fn timing(&self) -> Result<I2CTiming, I2CTimingError> {
+        if self.digital_filter > 15 {
+            return Err(I2CTimingError::DigitalFilter);
+        }
+        let spec = self.speed.spec();
+        let rise = u64::from(self.rise_time) * 1000;
+        let fall = u64::from(self.fall_time) * 1000;
+        if rise > spec.rise_max || fall > spec.fall_max {
+            return Err(I2CTimingError::RiseFall);
+        }
+        if self.kernel_clock == 0 {
+            return Err(I2CTimingError::Clock);
+        }
+        let clk = 1_000_000_000_000 / u64::from(self.kernel_clock);
+        let bus = 1_000_000_000_000 / spec.rate;
+        let bus_max = 1_000_000_000_000 / spec.rate_min;
+        let dnf = u64::from(self.digital_filter);
+        let (af_min, af_max) = if self.analog_filter {
+            (AF_DELAY_MIN, AF_DELAY_MAX)
+        } else {
+            (0, 0)
+        };
+        // The kernel clock period must be less than a quarter of the filtered
+        // SCL low period and less than the SCL high period.
+        if 4 * clk >= spec.low_min.saturating_sub(af_min + dnf * clk) || clk >= spec.high_min {
+            return Err(I2CTimingError::Clock);
+        }
+        let sdadel_min = (fall + spec.hddat_min).saturating_sub(af_min + (dnf + 3) * clk);
+        let sdadel_max = spec
+            .vddat_max
+            .checked_sub(rise + af_max + (dnf + 4) * clk)
+            .ok_or(I2CTimingError::DataHold)?;
+        if sdadel_min > sdadel_max {
+            return Err(I2CTimingError::DataHold);
+        }
+        let scldel_min = rise + spec.sudat_min;
+        let sync = af_min + dnf * clk + 2 * clk;
+        let mut data_hold = false;
+        for presc in 0..16 {
+            let presc_clk = (presc + 1) * clk;
+            let scldel = div_ceil(scldel_min, presc_clk).saturating_sub(1);
+            let sdadel = div_ceil(sdadel_min.saturating_sub(clk), presc_clk);
+            if scldel > 15 || sdadel > 15 || sdadel * presc_clk + clk > sdadel_max {
+                continue;
+            }
+            data_hold = true;
+            let mut scll = div_ceil(spec.low_min.saturating_sub(sync), presc_clk).saturating_sub(1);
+            let sclh = div_ceil(spec.high_min.saturating_sub(sync), presc_clk).saturating_sub(1);
+            let period = 2 * sync + (scll + sclh + 2) * presc_clk + rise + fall;
+            if period < bus {
+                scll += div_ceil(bus - period, presc_clk);
+            } else if period > bus_max {
+                continue;
+            }
+            if scll > 255 || sclh > 255 {
+                continue;
+            }
+            return Ok(I2CTiming {
+                presc: presc as u32,
+                scldel: scldel as u32,
+                sdadel: sdadel as u32,
+                sclh: sclh as u32,
+                scll: scll as u32,
+            });
+        }
+        if data_hold {
+            Err(I2CTimingError::Clock)
+        } else {
+            Err(I2CTimingError::DataHold)
+        }
+    }
+}
+
+impl I2CTiming {
+    /// Returns the raw `I2C_TIMINGR` value.
+    pub const fn to_bits(self) -> u32 {
+        self.presc << 28 | self.scldel << 20 | self.sdadel << 16 | self.sclh << 8 | self.scll
+    }
+}
+
+impl I2CSpeed {
+    fn spec(self) -> Spec {
+        match self {
+            Self::Standard => Spec {
+                rate: 100_000,
+                rate_min: 80_000,
+                hddat_min: 0,
+                vddat_max: 3_450_000,
+                sudat_min: 250_000,
+                low_min: 4_700_000,
+                high_min: 4_000_000,
+                rise_max: 1_000_000,
+                fall_max: 300_000,
+            },
+            Self::Fast => Spec {
+                rate: 400_000,
+                rate_min: 320_000,
+                hddat_min: 0,
+                vddat_max: 900_000,
+                sudat_min: 100_000,
+                low_min: 1_300_000,
+                high_min: 600_000,
+                rise_max: 300_000,
+                fall_max: 300_000,
+            },
+            Self::FastPlus => Spec {
+                rate: 1_000_000,
+                rate_min: 800_000,
+                hddat_min: 0,
+                vddat_max: 450_000,
+                sudat_min: 50_000,
+                low_min: 500_000,
+                high_min: 260_000,
+                rise_max: 120_000,
+                fall_max: 120_000,
+            },
+        }
+    }
+}
+
+impl fmt::Display for I2CTimingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DigitalFilter => write!(f, "I2C digital filter is too long."),
+            Self::RiseFall => write!(f, "I2C rise or fall time is out of specification."),
+            Self::DataHold => write!(f, "I2C data hold time is unreachable."),
+            Self::Clock => write!(f, "I2C kernel clock doesn't fit the bus speed."),
+        }
+    }
+}
+
+fn div_ceil(a: u64, b: u64) -> u64 {
+    (a + b - 1) / b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(kernel_clock: u32, speed: I2CSpeed) -> I2CTimingConfig {
+        I2CTimingConfig {
+            kernel_clock,
+            speed,
+            rise_time: 0,
+            fall_time: 0,
+            analog_filter: true,
+            digital_filter: 0,
+        }
+    }
+
+    #[test]
+    fn standard_80mhz() {
+        let timing = config(80_000_000, I2CSpeed::Standard).timing().unwrap();
+        assert_eq!(timing.to_bits(), 0x1090_9CEC);
+    }
+
+    #[test]
+    fn fast_80mhz() {
+        let timing = config(80_000_000, I2CSpeed::Fast).timing().unwrap();
+        assert_eq!(timing.to_bits(), 0x0070_2991);
+    }
+
+    #[test]
+    fn slow_clock() {
+        match config(2_000_000, I2CSpeed::Fast).timing() {
+            Err(I2CTimingError::Clock) => {}
+            timing => panic!("unexpected {:?}", timing),
+        }
+    }
+}
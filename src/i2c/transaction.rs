@@ -0,0 +1,196 @@
+use super::{I2CAddr, I2CDmaError, I2CEn};
+use crate::dma::{DmaChEn, DmaReadBuffer, DmaWriteBuffer};
+use drone_cortex_m::{reg::prelude::*, thr::prelude::*};
+use drone_stm32_map::periph::{
+    dma::ch::DmaChMap,
+    i2c::{traits::*, I2CMap},
+};
+use futures::prelude::*;
+
+/// I2C transaction operation.
+pub enum I2COp<W, R> {
+    /// Writes bytes from the buffer.
+    Write(W),
+    /// Reads bytes to the buffer.
+    Read(R),
+}
+
+impl<T: I2CMap, Ev: IntToken, Er: IntToken> I2CEn<T, Ev, Er> {
+    /// Writes bytes from `tx_buf` to `slave_addr`, then reads bytes to
+    /// `rx_buf` after a repeated START. Closes the session afterwards.
+    ///
+    /// The current `I2C_CR1` configuration is preserved. The returned future
+    /// gives back the DMA channels and the buffers. On error the session is
+    /// closed with a STOP condition.
+    pub fn write_read<'a, Tx, TxI, Rx, RxI, TxB, RxB>(
+        &'a self,
+        dma_tx: DmaChEn<Tx, TxI>,
+        dma_rx: DmaChEn<Rx, RxI>,
+        slave_addr: impl Into<I2CAddr>,
+        tx_buf: TxB,
+        rx_buf: RxB,
+    ) -> impl Future<
+        Output = (
+            DmaChEn<Tx, TxI>,
+            DmaChEn<Rx, RxI>,
+            TxB,
+            RxB,
+            Result<(), I2CDmaError>,
+        ),
+    > + 'a
+    where
+        Tx: DmaChMap,
+        TxI: IntToken,
+        Rx: DmaChMap,
+        RxI: IntToken,
+        TxB: DmaReadBuffer<Word = u8>,
+        RxB: DmaWriteBuffer<Word = u8>,
+    {
+        self.write_read_impl(dma_tx, dma_rx, slave_addr.into(), tx_buf, rx_buf)
+    }
+
+    /// Performs `ops` on `slave_addr` in a single session, separated by
+    /// repeated START conditions. Closes the session afterwards.
+    ///
+    /// The current `I2C_CR1` configuration is preserved. The returned future
+    /// gives back the DMA channels and the operations with their buffers.
+    /// Operations after a failed one are not performed. On error the session
+    /// is closed with a STOP condition.
+    pub fn transaction<'a, Tx, TxI, Rx, RxI, TxB, RxB>(
+        &'a self,
+        dma_tx: DmaChEn<Tx, TxI>,
+        dma_rx: DmaChEn<Rx, RxI>,
+        slave_addr: impl Into<I2CAddr>,
+        ops: Vec<I2COp<TxB, RxB>>,
+    ) -> impl Future<
+        Output = (
+            DmaChEn<Tx, TxI>,
+            DmaChEn<Rx, RxI>,
+            Vec<I2COp<TxB, RxB>>,
+            Result<(), I2CDmaError>,
+        ),
+    > + 'a
+    where
+        Tx: DmaChMap,
+        TxI: IntToken,
+        Rx: DmaChMap,
+        RxI: IntToken,
+        TxB: DmaReadBuffer<Word = u8>,
+        RxB: DmaWriteBuffer<Word = u8>,
+    {
+        self.transaction_impl(dma_tx, dma_rx, slave_addr.into(), ops)
+    }
+
+    async fn write_read_impl<Tx, TxI, Rx, RxI, TxB, RxB>(
+        &self,
+        dma_tx: DmaChEn<Tx, TxI>,
+        dma_rx: DmaChEn<Rx, RxI>,
+        slave_addr: I2CAddr,
+        tx_buf: TxB,
+        rx_buf: RxB,
+    ) -> (
+        DmaChEn<Tx, TxI>,
+        DmaChEn<Rx, RxI>,
+        TxB,
+        RxB,
+        Result<(), I2CDmaError>,
+    )
+    where
+        Tx: DmaChMap,
+        TxI: IntToken,
+        Rx: DmaChMap,
+        RxI: IntToken,
+        TxB: DmaReadBuffer<Word = u8>,
+        RxB: DmaWriteBuffer<Word = u8>,
+    {
+        let i2c_cr1_val = self.session_cr1_val();
+        let i2c_cr2_val = self.periph.i2c_cr2.default_val();
+        let (dma_tx, tx_buf, _, result) = self
+            .write_impl(dma_tx, tx_buf, slave_addr, i2c_cr1_val, i2c_cr2_val, false)
+            .await;
+        if let Err(err) = result {
+            self.stop_on_error();
+            return (dma_tx, dma_rx, tx_buf, rx_buf, Err(err));
+        }
+        let i2c_cr2_val = self.periph.i2c_cr2.default_val();
+        let (dma_rx, rx_buf, _, result) = self
+            .read_impl(dma_rx, rx_buf, slave_addr, i2c_cr1_val, i2c_cr2_val, true)
+            .await;
+        if result.is_err() {
+            self.stop_on_error();
+        }
+        (dma_tx, dma_rx, tx_buf, rx_buf, result)
+    }
+
+    async fn transaction_impl<Tx, TxI, Rx, RxI, TxB, RxB>(
+        &self,
+        mut dma_tx: DmaChEn<Tx, TxI>,
+        mut dma_rx: DmaChEn<Rx, RxI>,
+        slave_addr: I2CAddr,
+        ops: Vec<I2COp<TxB, RxB>>,
+    ) -> (
+        DmaChEn<Tx, TxI>,
+        DmaChEn<Rx, RxI>,
+        Vec<I2COp<TxB, RxB>>,
+        Result<(), I2CDmaError>,
+    )
+    where
+        Tx: DmaChMap,
+        TxI: IntToken,
+        Rx: DmaChMap,
+        RxI: IntToken,
+        TxB: DmaReadBuffer<Word = u8>,
+        RxB: DmaWriteBuffer<Word = u8>,
+    {
+        let i2c_cr1_val = self.session_cr1_val();
+        let last = ops.len().saturating_sub(1);
+        let mut done = Vec::with_capacity(ops.len());
+        let mut result = Ok(());
+        for (i, op) in ops.into_iter().enumerate() {
+            if result.is_err() {
+                done.push(op);
+                continue;
+            }
+            let autoend = i == last;
+            match op {
+                I2COp::Write(buf) => {
+                    let i2c_cr2_val = self.periph.i2c_cr2.default_val();
+                    let (tx, buf, _, res) = self
+                        .write_impl(dma_tx, buf, slave_addr, i2c_cr1_val, i2c_cr2_val, autoend)
+                        .await;
+                    dma_tx = tx;
+                    done.push(I2COp::Write(buf));
+                    result = res;
+                }
+                I2COp::Read(buf) => {
+                    let i2c_cr2_val = self.periph.i2c_cr2.default_val();
+                    let (rx, buf, _, res) = self
+                        .read_impl(dma_rx, buf, slave_addr, i2c_cr1_val, i2c_cr2_val, autoend)
+                        .await;
+                    dma_rx = rx;
+                    done.push(I2COp::Read(buf));
+                    result = res;
+                }
+            }
+        }
+        if result.is_err() {
+            self.stop_on_error();
+        }
+        (dma_tx, dma_rx, done, result)
+    }
+
+    /// Returns the current `I2C_CR1` value with the DMA requests disabled.
+    pub(super) fn session_cr1_val(&self) -> T::I2CCr1Val {
+        let mut val = self.periph.i2c_cr1.load_val();
+        self.periph.i2c_cr1.rxdmaen().clear(&mut val);
+        self.periph.i2c_cr1.txdmaen().clear(&mut val);
+        val
+    }
+
+    /// Closes the session if the bus is still held after a failed operation.
+    pub(super) fn stop_on_error(&self) {
+        if self.periph.i2c_isr.busy().read_bit_band() {
+            self.periph.i2c_cr2.stop().set_bit();
+        }
+    }
+}
@@ -26,15 +26,35 @@
 )]
 #![cfg_attr(not(feature = "std"), no_std)]
 
+#[macro_use]
+mod dma_cfg;
+#[macro_use]
+mod pin_map;
+
 #[cfg(feature = "adc")]
 pub mod adc;
+#[cfg(all(
+    feature = "afio",
+    any(
+        stm32_mcu = "stm32f100",
+        stm32_mcu = "stm32f101",
+        stm32_mcu = "stm32f102",
+        stm32_mcu = "stm32f103",
+        stm32_mcu = "stm32f107"
+    )
+))]
+pub mod afio;
 pub mod common;
 #[cfg(feature = "dma")]
 pub mod dma;
+#[cfg(feature = "exti")]
+pub mod exti;
 #[cfg(feature = "gpio")]
 pub mod gpio;
 #[cfg(feature = "i2c")]
 pub mod i2c;
+#[cfg(all(feature = "gpio", feature = "tim"))]
+pub mod soft_i2c;
 #[cfg(feature = "spi")]
 pub mod spi;
 #[cfg(feature = "tim")]
@@ -0,0 +1,28 @@
+#[allow(unused_macros)]
+macro_rules! pin_map {
+    (@remap) => {
+        ()
+    };
+    (@remap $remap:ty) => {
+        $remap
+    };
+    ($(
+        $trait:ident<$periph:ident> {
+            $($pin:ident => $af:ident $(if $remap:ty)?,)*
+        }
+    )*) => {
+        $($(
+            impl $trait<$periph> for $pin {
+                type Af = $af;
+                #[cfg(any(
+                    stm32_mcu = "stm32f100",
+                    stm32_mcu = "stm32f101",
+                    stm32_mcu = "stm32f102",
+                    stm32_mcu = "stm32f103",
+                    stm32_mcu = "stm32f107"
+                ))]
+                type Remap = pin_map!(@remap $($remap)?);
+            }
+        )*)*
+    };
+}
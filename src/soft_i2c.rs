@@ -0,0 +1,341 @@
+//! Software Inter-Integrated Circuit master over GPIO pins.
+
+use crate::gpio::{GpioPin, OpenDrain, Output};
+use core::{fmt, num::NonZeroUsize};
+use drone_cortex_m::drv::timer::Timer;
+use drone_stm32_map::periph::gpio::pin::GpioPinMap;
+use futures::prelude::*;
+
+/// Number of `half_period` delays a slave is allowed to stretch the clock for.
+const STRETCH_LIMIT: usize = 1000;
+
+/// Software I2C error.
+#[derive(Debug)]
+pub enum SoftI2CError {
+    /// NACK reception.
+    Nack,
+    /// Arbitration lost. The lines are released without a STOP condition.
+    Arlo,
+    /// SCL line is held low for too long.
+    Timeout,
+}
+
+/// Software I2C master driver.
+pub struct SoftI2C<Scl: GpioPinMap, Sda: GpioPinMap, T: Timer> {
+    scl: GpioPin<Scl, Output<OpenDrain>>,
+    sda: GpioPin<Sda, Output<OpenDrain>>,
+    timer: T,
+    half_period: u32,
+    session: bool,
+}
+
+struct Bus<'a, Scl: GpioPinMap, Sda: GpioPinMap, S: Stream<Item = NonZeroUsize> + Unpin> {
+    scl: &'a GpioPin<Scl, Output<OpenDrain>>,
+    sda: &'a GpioPin<Sda, Output<OpenDrain>>,
+    ticks: S,
+}
+
+impl<Scl: GpioPinMap, Sda: GpioPinMap, T: Timer> SoftI2C<Scl, Sda, T> {
+    /// Creates a new [`SoftI2C`]. `half_period` is a half of the SCL clock
+    /// period in `timer` ticks.
+    ///
+    /// Both lines are released.
+    pub fn new(
+        scl: GpioPin<Scl, Output<OpenDrain>>,
+        sda: GpioPin<Sda, Output<OpenDrain>>,
+        timer: T,
+        half_period: u32,
+    ) -> Self {
+        scl.set_high();
+        sda.set_high();
+        Self {
+            scl,
+            sda,
+            timer,
+            half_period,
+            session: false,
+        }
+    }
+
+    /// Releases the pins and the timer.
+    pub fn free(
+        self,
+    ) -> (
+        GpioPin<Scl, Output<OpenDrain>>,
+        GpioPin<Sda, Output<OpenDrain>>,
+        T,
+    ) {
+        (self.scl, self.sda, self.timer)
+    }
+
+    /// Reads bytes to `buf` from `slave_addr`. Leaves the session open.
+    pub fn read<'a>(
+        &'a mut self,
+        buf: &'a mut [u8],
+        slave_addr: u8,
+    ) -> impl Future<Output = Result<(), SoftI2CError>> + 'a {
+        self.read_impl(buf, slave_addr, false)
+    }
+
+    /// Reads bytes to `buf` from `slave_addr`. Closes the session afterwards.
+    pub fn read_and_stop<'a>(
+        &'a mut self,
+        buf: &'a mut [u8],
+        slave_addr: u8,
+    ) -> impl Future<Output = Result<(), SoftI2CError>> + 'a {
+        self.read_impl(buf, slave_addr, true)
+    }
+
+    /// Writes bytes from `buf` to `slave_addr`. Leaves the session open.
+    pub fn write<'a>(
+        &'a mut self,
+        buf: &'a [u8],
+        slave_addr: u8,
+    ) -> impl Future<Output = Result<(), SoftI2CError>> + 'a {
+        self.write_impl(buf, slave_addr, false)
+    }
+
+    /// Writes bytes from `buf` to `slave_addr`. Closes the session afterwards.
+    pub fn write_and_stop<'a>(
+        &'a mut self,
+        buf: &'a [u8],
+        slave_addr: u8,
+    ) -> impl Future<Output = Result<(), SoftI2CError>> + 'a {
+        self.write_impl(buf, slave_addr, true)
+    }
+
+    /// Clocks out up to nine SCL pulses until a slave holding SDA low releases
+    /// it, then closes the session with a STOP condition.
+    pub fn clear_bus(&mut self) -> impl Future<Output = Result<(), SoftI2CError>> + '_ {
+        self.clear_bus_impl()
+    }
+
+    async fn read_impl(
+        &mut self,
+        buf: &mut [u8],
+        slave_addr: u8,
+        autoend: bool,
+    ) -> Result<(), SoftI2CError> {
+        let Self {
+            scl,
+            sda,
+            timer,
+            half_period,
+            session,
+        } = self;
+        let mut bus = Bus {
+            scl,
+            sda,
+            ticks: timer.interval_skip(*half_period),
+        };
+        let result = async {
+            bus.start(*session).await?;
+            *session = true;
+            bus.write_byte(slave_addr << 1 | 1).await?;
+            let last = buf.len().saturating_sub(1);
+            for (i, byte) in buf.iter_mut().enumerate() {
+                *byte = bus.read_byte(i != last).await?;
+            }
+            Ok::<_, SoftI2CError>(())
+        }
+        .await;
+        if let Err(SoftI2CError::Arlo) = result {
+            // The bus is owned by another master, so the lines are released
+            // without a STOP condition.
+            *session = false;
+            bus.release();
+            return result;
+        }
+        if autoend || result.is_err() {
+            *session = false;
+            // The STOP result is ignored after an error to keep the first one.
+            let stop = bus.stop().await;
+            return result.and(stop);
+        }
+        result
+    }
+
+    async fn clear_bus_impl(&mut self) -> Result<(), SoftI2CError> {
+        let Self {
+            scl,
+            sda,
+            timer,
+            half_period,
+            session,
+        } = self;
+        let mut bus = Bus {
+            scl,
+            sda,
+            ticks: timer.interval_skip(*half_period),
+        };
+        *session = false;
+        bus.sda.set_high();
+        for _ in 0..9 {
+            if bus.sda.is_high() {
+                break;
+            }
+            bus.scl.set_low();
+            bus.delay().await;
+            bus.scl_release().await?;
+            bus.delay().await;
+        }
+        bus.scl.set_low();
+        bus.delay().await;
+        bus.stop().await
+    }
+
+    async fn write_impl(
+        &mut self,
+        buf: &[u8],
+        slave_addr: u8,
+        autoend: bool,
+    ) -> Result<(), SoftI2CError> {
+        let Self {
+            scl,
+            sda,
+            timer,
+            half_period,
+            session,
+        } = self;
+        let mut bus = Bus {
+            scl,
+            sda,
+            ticks: timer.interval_skip(*half_period),
+        };
+        let result = async {
+            bus.start(*session).await?;
+            *session = true;
+            bus.write_byte(slave_addr << 1).await?;
+            for &byte in buf {
+                bus.write_byte(byte).await?;
+            }
+            Ok::<_, SoftI2CError>(())
+        }
+        .await;
+        if let Err(SoftI2CError::Arlo) = result {
+            // The bus is owned by another master, so the lines are released
+            // without a STOP condition.
+            *session = false;
+            bus.release();
+            return result;
+        }
+        if autoend || result.is_err() {
+            *session = false;
+            // The STOP result is ignored after an error to keep the first one.
+            let stop = bus.stop().await;
+            return result.and(stop);
+        }
+        result
+    }
+}
+
+impl<'a, Scl, Sda, S> Bus<'a, Scl, Sda, S>
+where
+    Scl: GpioPinMap,
+    Sda: GpioPinMap,
+    S: Stream<Item = NonZeroUsize> + Unpin,
+{
+    fn release(&self) {
+        self.scl.set_high();
+        self.sda.set_high();
+    }
+
+    async fn delay(&mut self) {
+        self.ticks.next().await;
+    }
+
+    async fn scl_release(&mut self) -> Result<(), SoftI2CError> {
+        self.scl.set_high();
+        for _ in 0..STRETCH_LIMIT {
+            if self.scl.is_high() {
+                return Ok(());
+            }
+            self.delay().await;
+        }
+        Err(SoftI2CError::Timeout)
+    }
+
+    async fn start(&mut self, repeated: bool) -> Result<(), SoftI2CError> {
+        if repeated {
+            self.sda.set_high();
+            self.delay().await;
+            self.scl_release().await?;
+            self.delay().await;
+        }
+        if self.sda.is_low() {
+            return Err(SoftI2CError::Arlo);
+        }
+        self.sda.set_low();
+        self.delay().await;
+        self.scl.set_low();
+        Ok(())
+    }
+
+    async fn stop(&mut self) -> Result<(), SoftI2CError> {
+        self.sda.set_low();
+        self.delay().await;
+        self.scl_release().await?;
+        self.delay().await;
+        self.sda.set_high();
+        self.delay().await;
+        if self.sda.is_low() {
+            return Err(SoftI2CError::Arlo);
+        }
+        Ok(())
+    }
+
+    async fn write_bit(&mut self, bit: bool) -> Result<(), SoftI2CError> {
+        if bit {
+            self.sda.set_high();
+        } else {
+            self.sda.set_low();
+        }
+        self.delay().await;
+        self.scl_release().await?;
+        if bit && self.sda.is_low() {
+            return Err(SoftI2CError::Arlo);
+        }
+        self.delay().await;
+        self.scl.set_low();
+        Ok(())
+    }
+
+    async fn read_bit(&mut self) -> Result<bool, SoftI2CError> {
+        self.sda.set_high();
+        self.delay().await;
+        self.scl_release().await?;
+        let bit = self.sda.is_high();
+        self.delay().await;
+        self.scl.set_low();
+        Ok(bit)
+    }
+
+    async fn write_byte(&mut self, byte: u8) -> Result<(), SoftI2CError> {
+        for i in (0..8).rev() {
+            self.write_bit(byte >> i & 1 != 0).await?;
+        }
+        if self.read_bit().await? {
+            return Err(SoftI2CError::Nack);
+        }
+        Ok(())
+    }
+
+    async fn read_byte(&mut self, ack: bool) -> Result<u8, SoftI2CError> {
+        let mut byte = 0;
+        for _ in 0..8 {
+            byte = byte << 1 | u8::from(self.read_bit().await?);
+        }
+        self.write_bit(!ack).await?;
+        Ok(byte)
+    }
+}
+
+impl fmt::Display for SoftI2CError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Nack => write!(f, "I2C NACK received."),
+            Self::Arlo => write!(f, "I2C arbitration lost."),
+            Self::Timeout => write!(f, "I2C clock stretching timeout."),
+        }
+    }
+}
@@ -1,19 +1,50 @@
 //! Serial Peripheral Interface.
 
-use crate::{
-    common::{DrvDmaRx, DrvDmaTx, DrvRcc},
-    dma::DmaChEn,
-};
+#[cfg(any(
+    stm32_mcu = "stm32l4x1",
+    stm32_mcu = "stm32l4x2",
+    stm32_mcu = "stm32l4x3",
+    stm32_mcu = "stm32l4x5",
+    stm32_mcu = "stm32l4x6",
+    stm32_mcu = "stm32l4r5",
+    stm32_mcu = "stm32l4r7",
+    stm32_mcu = "stm32l4r9",
+    stm32_mcu = "stm32l4s5",
+    stm32_mcu = "stm32l4s7",
+    stm32_mcu = "stm32l4s9"
+))]
+use crate::common::{DmaRxReq, DmaTxReq};
+use crate::common::{DrvDmaRx, DrvDmaTx, DrvRcc};
+#[cfg(feature = "gpio")]
+#[cfg(any(
+    stm32_mcu = "stm32f100",
+    stm32_mcu = "stm32f101",
+    stm32_mcu = "stm32f102",
+    stm32_mcu = "stm32f103",
+    stm32_mcu = "stm32f107"
+))]
+use crate::gpio::Input;
+#[cfg(feature = "gpio")]
+use crate::gpio::{Alternate, GpioPin, PinAf, PinMode, PushPull};
 use core::{
     fmt,
     ptr::{read_volatile, write_volatile},
 };
 use drone_core::inventory::{self, Inventory0, Inventory1};
 use drone_cortex_m::{reg::prelude::*, thr::prelude::*};
-use drone_stm32_map::periph::{
-    dma::ch::DmaChMap,
-    spi::{traits::*, SpiMap, SpiPeriph},
-};
+#[cfg(feature = "gpio")]
+use drone_stm32_map::periph::gpio::pin::GpioPinMap;
+use drone_stm32_map::periph::spi::{traits::*, SpiMap, SpiPeriph};
+
+dma_ch! {
+    use crate::dma::DmaChEn;
+    use drone_stm32_map::periph::dma::ch::DmaChMap;
+}
+
+dma_st! {
+    use crate::dma::DmaStEn;
+    use drone_stm32_map::periph::dma::st::DmaStMap;
+}
 
 /// Motorola SPI mode error.
 #[derive(Debug)]
@@ -26,6 +57,54 @@ pub enum SpiError {
     Modf,
 }
 
+/// GPIO pin, which can carry the SCK signal of the SPI `T`.
+#[cfg(feature = "gpio")]
+pub trait SpiSckPin<T: SpiMap>: GpioPinMap {
+    /// Alternate function of the signal.
+    type Af: PinAf;
+    /// Proof of the AFIO pins mapping required by the pin, `()` if none.
+    #[cfg(any(
+        stm32_mcu = "stm32f100",
+        stm32_mcu = "stm32f101",
+        stm32_mcu = "stm32f102",
+        stm32_mcu = "stm32f103",
+        stm32_mcu = "stm32f107"
+    ))]
+    type Remap;
+}
+
+/// GPIO pin, which can carry the MISO signal of the SPI `T`.
+#[cfg(feature = "gpio")]
+pub trait SpiMisoPin<T: SpiMap>: GpioPinMap {
+    /// Alternate function of the signal.
+    type Af: PinAf;
+    /// Proof of the AFIO pins mapping required by the pin, `()` if none.
+    #[cfg(any(
+        stm32_mcu = "stm32f100",
+        stm32_mcu = "stm32f101",
+        stm32_mcu = "stm32f102",
+        stm32_mcu = "stm32f103",
+        stm32_mcu = "stm32f107"
+    ))]
+    type Remap;
+}
+
+/// GPIO pin, which can carry the MOSI signal of the SPI `T`.
+#[cfg(feature = "gpio")]
+pub trait SpiMosiPin<T: SpiMap>: GpioPinMap {
+    /// Alternate function of the signal.
+    type Af: PinAf;
+    /// Proof of the AFIO pins mapping required by the pin, `()` if none.
+    #[cfg(any(
+        stm32_mcu = "stm32f100",
+        stm32_mcu = "stm32f101",
+        stm32_mcu = "stm32f102",
+        stm32_mcu = "stm32f103",
+        stm32_mcu = "stm32f107"
+    ))]
+    type Remap;
+}
+
 /// SPI driver.
 pub struct Spi<T: SpiMap, I: IntToken>(Inventory0<SpiEn<T, I>>);
 
@@ -234,36 +313,236 @@ impl<T: SpiMap, I: IntToken> SpiEn<T, I> {
     }
 }
 
+#[cfg(all(
+    feature = "gpio",
+    any(
+        stm32_mcu = "stm32f100",
+        stm32_mcu = "stm32f101",
+        stm32_mcu = "stm32f102",
+        stm32_mcu = "stm32f103",
+        stm32_mcu = "stm32f107"
+    )
+))]
+impl<T: SpiMap, I: IntToken> SpiEn<T, I> {
+    /// Switches `sck` and `mosi` pins to the alternate functions of the SPI,
+    /// and `miso` pin to the floating input.
+    ///
+    /// STM32F1 requires the MISO pin of the master to be configured as an
+    /// input. The pull-up can be enabled with
+    /// [`set_pull`](GpioPin::set_pull) afterwards.
+    ///
+    /// `_remap` is the proof of the pins mapping returned by
+    /// `AfioEn::set_remap`, or `()` for the peripherals without remapping.
+    #[allow(clippy::type_complexity)]
+    pub fn setup_pins<Sck, Miso, Mosi>(
+        &self,
+        sck: GpioPin<Sck, impl PinMode>,
+        miso: GpioPin<Miso, impl PinMode>,
+        mosi: GpioPin<Mosi, impl PinMode>,
+        _remap: Sck::Remap,
+    ) -> (
+        GpioPin<Sck, Alternate<Sck::Af, PushPull>>,
+        GpioPin<Miso, Input>,
+        GpioPin<Mosi, Alternate<Mosi::Af, PushPull>>,
+    )
+    where
+        Sck: SpiSckPin<T>,
+        Miso: SpiMisoPin<T, Remap = Sck::Remap>,
+        Mosi: SpiMosiPin<T, Remap = Sck::Remap>,
+    {
+        (
+            sck.into_alternate(),
+            miso.into_input(),
+            mosi.into_alternate(),
+        )
+    }
+}
+
+#[cfg(all(
+    feature = "gpio",
+    not(any(
+        stm32_mcu = "stm32f100",
+        stm32_mcu = "stm32f101",
+        stm32_mcu = "stm32f102",
+        stm32_mcu = "stm32f103",
+        stm32_mcu = "stm32f107"
+    ))
+))]
+impl<T: SpiMap, I: IntToken> SpiEn<T, I> {
+    /// Switches `sck`, `miso`, and `mosi` pins to the alternate functions of
+    /// the SPI.
+    #[allow(clippy::type_complexity)]
+    pub fn setup_pins<Sck, Miso, Mosi>(
+        &self,
+        sck: GpioPin<Sck, impl PinMode>,
+        miso: GpioPin<Miso, impl PinMode>,
+        mosi: GpioPin<Mosi, impl PinMode>,
+    ) -> (
+        GpioPin<Sck, Alternate<Sck::Af, PushPull>>,
+        GpioPin<Miso, Alternate<Miso::Af, PushPull>>,
+        GpioPin<Mosi, Alternate<Mosi::Af, PushPull>>,
+    )
+    where
+        Sck: SpiSckPin<T>,
+        Miso: SpiMisoPin<T>,
+        Mosi: SpiMosiPin<T>,
+    {
+        (
+            sck.into_alternate(),
+            miso.into_alternate(),
+            mosi.into_alternate(),
+        )
+    }
+}
+
 impl<T: SpiMap, I: IntToken> inventory::Item for SpiEn<T, I> {
     fn teardown(&mut self, _token: &mut inventory::GuardToken<Self>) {
         self.periph.rcc_busenr_spien.clear_bit()
     }
 }
 
-impl<T: SpiMap, I: IntToken, Rx: DmaChMap> DrvDmaRx<Rx> for Spi<T, I> {
-    #[inline]
-    fn dma_rx_paddr_init(&self, dma_rx: &DmaChEn<Rx, impl IntToken>) {
-        self.0.dma_rx_paddr_init(dma_rx);
+dma_ch! {
+    impl<T: SpiMap, I: IntToken, Rx: DmaChMap> DrvDmaRx<Rx> for Spi<T, I> {
+        #[inline]
+        fn dma_rx_paddr_init(&self, dma_rx: &DmaChEn<Rx, impl IntToken>) {
+            self.0.dma_rx_paddr_init(dma_rx);
+        }
     }
-}
 
-impl<T: SpiMap, I: IntToken, Tx: DmaChMap> DrvDmaTx<Tx> for Spi<T, I> {
-    #[inline]
-    fn dma_tx_paddr_init(&self, dma_tx: &DmaChEn<Tx, impl IntToken>) {
-        self.0.dma_tx_paddr_init(dma_tx);
+    impl<T: SpiMap, I: IntToken, Tx: DmaChMap> DrvDmaTx<Tx> for Spi<T, I> {
+        #[inline]
+        fn dma_tx_paddr_init(&self, dma_tx: &DmaChEn<Tx, impl IntToken>) {
+            self.0.dma_tx_paddr_init(dma_tx);
+        }
+    }
+
+    impl<T: SpiMap, I: IntToken, Rx: DmaChMap> DrvDmaRx<Rx> for SpiEn<T, I> {
+        fn dma_rx_paddr_init(&self, dma_rx: &DmaChEn<Rx, impl IntToken>) {
+            unsafe { dma_rx.set_paddr(self.periph.spi_dr.as_ptr()) };
+        }
     }
-}
 
-impl<T: SpiMap, I: IntToken, Rx: DmaChMap> DrvDmaRx<Rx> for SpiEn<T, I> {
-    fn dma_rx_paddr_init(&self, dma_rx: &DmaChEn<Rx, impl IntToken>) {
-        unsafe { dma_rx.set_paddr(self.periph.spi_dr.as_ptr()) };
+    impl<T: SpiMap, I: IntToken, Tx: DmaChMap> DrvDmaTx<Tx> for SpiEn<T, I> {
+        fn dma_tx_paddr_init(&self, dma_tx: &DmaChEn<Tx, impl IntToken>) {
+            unsafe { dma_tx.set_paddr(self.periph.spi_dr.as_mut_ptr()) };
+        }
     }
 }
 
-impl<T: SpiMap, I: IntToken, Tx: DmaChMap> DrvDmaTx<Tx> for SpiEn<T, I> {
-    fn dma_tx_paddr_init(&self, dma_tx: &DmaChEn<Tx, impl IntToken>) {
-        unsafe { dma_tx.set_paddr(self.periph.spi_dr.as_mut_ptr()) };
+dma_st! {
+    impl<T: SpiMap, I: IntToken, Rx: DmaStMap> DrvDmaRx<Rx> for Spi<T, I> {
+        #[inline]
+        fn dma_rx_paddr_init(&self, dma_rx: &DmaStEn<Rx, impl IntToken>) {
+            self.0.dma_rx_paddr_init(dma_rx);
+        }
     }
+
+    impl<T: SpiMap, I: IntToken, Tx: DmaStMap> DrvDmaTx<Tx> for Spi<T, I> {
+        #[inline]
+        fn dma_tx_paddr_init(&self, dma_tx: &DmaStEn<Tx, impl IntToken>) {
+            self.0.dma_tx_paddr_init(dma_tx);
+        }
+    }
+
+    impl<T: SpiMap, I: IntToken, Rx: DmaStMap> DrvDmaRx<Rx> for SpiEn<T, I> {
+        fn dma_rx_paddr_init(&self, dma_rx: &DmaStEn<Rx, impl IntToken>) {
+            unsafe { dma_rx.set_paddr(self.periph.spi_dr.as_ptr()) };
+        }
+    }
+
+    impl<T: SpiMap, I: IntToken, Tx: DmaStMap> DrvDmaTx<Tx> for SpiEn<T, I> {
+        fn dma_tx_paddr_init(&self, dma_tx: &DmaStEn<Tx, impl IntToken>) {
+            unsafe { dma_tx.set_paddr(self.periph.spi_dr.as_mut_ptr()) };
+        }
+    }
+}
+
+#[cfg(any(
+    stm32_mcu = "stm32l4x1",
+    stm32_mcu = "stm32l4x2",
+    stm32_mcu = "stm32l4x3",
+    stm32_mcu = "stm32l4x5",
+    stm32_mcu = "stm32l4x6",
+    stm32_mcu = "stm32l4r5",
+    stm32_mcu = "stm32l4r7",
+    stm32_mcu = "stm32l4r9",
+    stm32_mcu = "stm32l4s5",
+    stm32_mcu = "stm32l4s7",
+    stm32_mcu = "stm32l4s9"
+))]
+impl<T, I, Rx> DmaRxReq<Rx> for Spi<T, I>
+where
+    T: SpiMap + DmaRxReq<Rx>,
+    I: IntToken,
+    Rx: DmaChMap,
+{
+    const DMA_RX_REQ: u32 = T::DMA_RX_REQ;
+}
+
+#[cfg(any(
+    stm32_mcu = "stm32l4x1",
+    stm32_mcu = "stm32l4x2",
+    stm32_mcu = "stm32l4x3",
+    stm32_mcu = "stm32l4x5",
+    stm32_mcu = "stm32l4x6",
+    stm32_mcu = "stm32l4r5",
+    stm32_mcu = "stm32l4r7",
+    stm32_mcu = "stm32l4r9",
+    stm32_mcu = "stm32l4s5",
+    stm32_mcu = "stm32l4s7",
+    stm32_mcu = "stm32l4s9"
+))]
+impl<T, I, Rx> DmaRxReq<Rx> for SpiEn<T, I>
+where
+    T: SpiMap + DmaRxReq<Rx>,
+    I: IntToken,
+    Rx: DmaChMap,
+{
+    const DMA_RX_REQ: u32 = T::DMA_RX_REQ;
+}
+
+#[cfg(any(
+    stm32_mcu = "stm32l4x1",
+    stm32_mcu = "stm32l4x2",
+    stm32_mcu = "stm32l4x3",
+    stm32_mcu = "stm32l4x5",
+    stm32_mcu = "stm32l4x6",
+    stm32_mcu = "stm32l4r5",
+    stm32_mcu = "stm32l4r7",
+    stm32_mcu = "stm32l4r9",
+    stm32_mcu = "stm32l4s5",
+    stm32_mcu = "stm32l4s7",
+    stm32_mcu = "stm32l4s9"
+))]
+impl<T, I, Tx> DmaTxReq<Tx> for Spi<T, I>
+where
+    T: SpiMap + DmaTxReq<Tx>,
+    I: IntToken,
+    Tx: DmaChMap,
+{
+    const DMA_TX_REQ: u32 = T::DMA_TX_REQ;
+}
+
+#[cfg(any(
+    stm32_mcu = "stm32l4x1",
+    stm32_mcu = "stm32l4x2",
+    stm32_mcu = "stm32l4x3",
+    stm32_mcu = "stm32l4x5",
+    stm32_mcu = "stm32l4x6",
+    stm32_mcu = "stm32l4r5",
+    stm32_mcu = "stm32l4r7",
+    stm32_mcu = "stm32l4r9",
+    stm32_mcu = "stm32l4s5",
+    stm32_mcu = "stm32l4s7",
+    stm32_mcu = "stm32l4s9"
+))]
+impl<T, I, Tx> DmaTxReq<Tx> for SpiEn<T, I>
+where
+    T: SpiMap + DmaTxReq<Tx>,
+    I: IntToken,
+    Tx: DmaChMap,
+{
+    const DMA_TX_REQ: u32 = T::DMA_TX_REQ;
 }
 
 impl<T: SpiMap, I: IntToken> DrvRcc for Spi<T, I> {
@@ -297,6 +576,315 @@ impl<T: SpiMap, I: IntToken> DrvRcc for SpiEn<T, I> {
     }
 }
 
+#[cfg(all(
+    feature = "gpio",
+    any(
+        stm32_mcu = "stm32f100",
+        stm32_mcu = "stm32f101",
+        stm32_mcu = "stm32f102",
+        stm32_mcu = "stm32f103",
+        stm32_mcu = "stm32f107"
+    )
+))]
+mod pins {
+    use super::{SpiMisoPin, SpiMosiPin, SpiSckPin};
+    #[cfg(feature = "afio")]
+    use crate::afio::{Spi1NotRemapped, Spi1Remapped};
+    use crate::gpio::AfRemap;
+    #[cfg(any(feature = "afio", not(stm32_mcu = "stm32f102")))]
+    use drone_stm32_map::periph::gpio::pin::{GpioB3, GpioB4, GpioB5};
+    #[cfg(not(stm32_mcu = "stm32f102"))]
+    use drone_stm32_map::periph::spi::Spi3;
+    use drone_stm32_map::periph::{
+        gpio::pin::{GpioA5, GpioA6, GpioA7, GpioB13, GpioB14, GpioB15},
+        spi::{Spi1, Spi2},
+    };
+
+    pin_map! {
+        SpiSckPin<Spi2> {
+            GpioB13 => AfRemap,
+        }
+        SpiMisoPin<Spi2> {
+            GpioB14 => AfRemap,
+        }
+        SpiMosiPin<Spi2> {
+            GpioB15 => AfRemap,
+        }
+    }
+
+    #[cfg(not(feature = "afio"))]
+    pin_map! {
+        SpiSckPin<Spi1> {
+            GpioA5 => AfRemap,
+        }
+        SpiMisoPin<Spi1> {
+            GpioA6 => AfRemap,
+        }
+        SpiMosiPin<Spi1> {
+            GpioA7 => AfRemap,
+        }
+    }
+
+    #[cfg(feature = "afio")]
+    pin_map! {
+        SpiSckPin<Spi1> {
+            GpioA5 => AfRemap if Spi1NotRemapped,
+            GpioB3 => AfRemap if Spi1Remapped,
+        }
+        SpiMisoPin<Spi1> {
+            GpioA6 => AfRemap if Spi1NotRemapped,
+            GpioB4 => AfRemap if Spi1Remapped,
+        }
+        SpiMosiPin<Spi1> {
+            GpioA7 => AfRemap if Spi1NotRemapped,
+            GpioB5 => AfRemap if Spi1Remapped,
+        }
+    }
+
+    #[cfg(not(stm32_mcu = "stm32f102"))]
+    pin_map! {
+        SpiSckPin<Spi3> {
+            GpioB3 => AfRemap,
+        }
+        SpiMisoPin<Spi3> {
+            GpioB4 => AfRemap,
+        }
+        SpiMosiPin<Spi3> {
+            GpioB5 => AfRemap,
+        }
+    }
+}
+
+#[cfg(all(
+    feature = "gpio",
+    any(
+        stm32_mcu = "stm32f401",
+        stm32_mcu = "stm32f405",
+        stm32_mcu = "stm32f407",
+        stm32_mcu = "stm32f410",
+        stm32_mcu = "stm32f411",
+        stm32_mcu = "stm32f412",
+        stm32_mcu = "stm32f413",
+        stm32_mcu = "stm32f427",
+        stm32_mcu = "stm32f429",
+        stm32_mcu = "stm32f446",
+        stm32_mcu = "stm32f469"
+    )
+))]
+mod pins {
+    use super::{SpiMisoPin, SpiMosiPin, SpiSckPin};
+    use crate::gpio::{Af5, Af6};
+    use drone_stm32_map::periph::{
+        gpio::pin::{
+            GpioA5, GpioA6, GpioA7, GpioB10, GpioB13, GpioB14, GpioB15, GpioB3, GpioB4, GpioB5,
+            GpioC2, GpioC3,
+        },
+        spi::{Spi1, Spi2},
+    };
+    #[cfg(not(stm32_mcu = "stm32f410"))]
+    use drone_stm32_map::periph::{
+        gpio::pin::{GpioC10, GpioC11, GpioC12},
+        spi::Spi3,
+    };
+
+    pin_map! {
+        SpiSckPin<Spi1> {
+            GpioA5 => Af5,
+            GpioB3 => Af5,
+        }
+        SpiMisoPin<Spi1> {
+            GpioA6 => Af5,
+            GpioB4 => Af5,
+        }
+        SpiMosiPin<Spi1> {
+            GpioA7 => Af5,
+            GpioB5 => Af5,
+        }
+        SpiSckPin<Spi2> {
+            GpioB10 => Af5,
+            GpioB13 => Af5,
+        }
+        SpiMisoPin<Spi2> {
+            GpioB14 => Af5,
+            GpioC2 => Af5,
+        }
+        SpiMosiPin<Spi2> {
+            GpioB15 => Af5,
+            GpioC3 => Af5,
+        }
+    }
+
+    #[cfg(not(stm32_mcu = "stm32f410"))]
+    pin_map! {
+        SpiSckPin<Spi3> {
+            GpioB3 => Af6,
+            GpioC10 => Af6,
+        }
+        SpiMisoPin<Spi3> {
+            GpioB4 => Af6,
+            GpioC11 => Af6,
+        }
+        SpiMosiPin<Spi3> {
+            GpioB5 => Af6,
+            GpioC12 => Af6,
+        }
+    }
+}
+
+#[cfg(all(
+    feature = "gpio",
+    any(
+        stm32_mcu = "stm32l4x1",
+        stm32_mcu = "stm32l4x2",
+        stm32_mcu = "stm32l4x3",
+        stm32_mcu = "stm32l4x5",
+        stm32_mcu = "stm32l4x6",
+        stm32_mcu = "stm32l4r5",
+        stm32_mcu = "stm32l4r7",
+        stm32_mcu = "stm32l4r9",
+        stm32_mcu = "stm32l4s5",
+        stm32_mcu = "stm32l4s7",
+        stm32_mcu = "stm32l4s9"
+    )
+))]
+mod pins {
+    use super::{SpiMisoPin, SpiMosiPin, SpiSckPin};
+    use crate::gpio::{Af5, Af6};
+    use drone_stm32_map::periph::{
+        gpio::pin::{
+            GpioA5, GpioA6, GpioA7, GpioB10, GpioB13, GpioB14, GpioB15, GpioB3, GpioB4, GpioB5,
+            GpioC10, GpioC11, GpioC12, GpioC2, GpioC3,
+        },
+        spi::{Spi1, Spi2, Spi3},
+    };
+
+    pin_map! {
+        SpiSckPin<Spi1> {
+            GpioA5 => Af5,
+            GpioB3 => Af5,
+        }
+        SpiMisoPin<Spi1> {
+            GpioA6 => Af5,
+            GpioB4 => Af5,
+        }
+        SpiMosiPin<Spi1> {
+            GpioA7 => Af5,
+            GpioB5 => Af5,
+        }
+        SpiSckPin<Spi2> {
+            GpioB10 => Af5,
+            GpioB13 => Af5,
+        }
+        SpiMisoPin<Spi2> {
+            GpioB14 => Af5,
+            GpioC2 => Af5,
+        }
+        SpiMosiPin<Spi2> {
+            GpioB15 => Af5,
+            GpioC3 => Af5,
+        }
+        SpiSckPin<Spi3> {
+            GpioB3 => Af6,
+            GpioC10 => Af6,
+        }
+        SpiMisoPin<Spi3> {
+            GpioB4 => Af6,
+            GpioC11 => Af6,
+        }
+        SpiMosiPin<Spi3> {
+            GpioB5 => Af6,
+            GpioC12 => Af6,
+        }
+    }
+}
+
+#[cfg(any(
+    stm32_mcu = "stm32l4x1",
+    stm32_mcu = "stm32l4x2",
+    stm32_mcu = "stm32l4x3",
+    stm32_mcu = "stm32l4x5",
+    stm32_mcu = "stm32l4x6"
+))]
+mod dma_req {
+    use crate::common::{DmaRxReq, DmaTxReq};
+    use drone_stm32_map::periph::{
+        dma::ch::{Dma1Ch2, Dma1Ch3, Dma1Ch4, Dma1Ch5, Dma2Ch1, Dma2Ch2, Dma2Ch3, Dma2Ch4},
+        spi::{Spi1, Spi2, Spi3},
+    };
+
+    impl DmaRxReq<Dma1Ch2> for Spi1 {
+        const DMA_RX_REQ: u32 = 1;
+    }
+
+    impl DmaTxReq<Dma1Ch3> for Spi1 {
+        const DMA_TX_REQ: u32 = 1;
+    }
+
+    impl DmaRxReq<Dma2Ch3> for Spi1 {
+        const DMA_RX_REQ: u32 = 4;
+    }
+
+    impl DmaTxReq<Dma2Ch4> for Spi1 {
+        const DMA_TX_REQ: u32 = 4;
+    }
+
+    impl DmaRxReq<Dma1Ch4> for Spi2 {
+        const DMA_RX_REQ: u32 = 1;
+    }
+
+    impl DmaTxReq<Dma1Ch5> for Spi2 {
+        const DMA_TX_REQ: u32 = 1;
+    }
+
+    impl DmaRxReq<Dma2Ch1> for Spi3 {
+        const DMA_RX_REQ: u32 = 3;
+    }
+
+    impl DmaTxReq<Dma2Ch2> for Spi3 {
+        const DMA_TX_REQ: u32 = 3;
+    }
+}
+
+#[cfg(any(
+    stm32_mcu = "stm32l4r5",
+    stm32_mcu = "stm32l4r7",
+    stm32_mcu = "stm32l4r9",
+    stm32_mcu = "stm32l4s5",
+    stm32_mcu = "stm32l4s7",
+    stm32_mcu = "stm32l4s9"
+))]
+mod dma_req {
+    use crate::common::{DmaRxReq, DmaTxReq};
+    use drone_stm32_map::periph::{
+        dma::ch::DmaChMap,
+        spi::{Spi1, Spi2, Spi3},
+    };
+
+    impl<Rx: DmaChMap> DmaRxReq<Rx> for Spi1 {
+        const DMA_RX_REQ: u32 = 10;
+    }
+
+    impl<Tx: DmaChMap> DmaTxReq<Tx> for Spi1 {
+        const DMA_TX_REQ: u32 = 11;
+    }
+
+    impl<Rx: DmaChMap> DmaRxReq<Rx> for Spi2 {
+        const DMA_RX_REQ: u32 = 12;
+    }
+
+    impl<Tx: DmaChMap> DmaTxReq<Tx> for Spi2 {
+        const DMA_TX_REQ: u32 = 13;
+    }
+
+    impl<Rx: DmaChMap> DmaRxReq<Rx> for Spi3 {
+        const DMA_RX_REQ: u32 = 14;
+    }
+
+    impl<Tx: DmaChMap> DmaTxReq<Tx> for Spi3 {
+        const DMA_TX_REQ: u32 = 15;
+    }
+}
+
 impl fmt::Display for SpiError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -1,9 +1,31 @@
 //! Universal Asynchronous Receiver/Transmitter.
 
-use crate::{
-    common::{DrvClockSel, DrvDmaRx, DrvDmaTx, DrvRcc},
-    dma::DmaChEn,
-};
+#[cfg(any(
+    stm32_mcu = "stm32l4x1",
+    stm32_mcu = "stm32l4x2",
+    stm32_mcu = "stm32l4x3",
+    stm32_mcu = "stm32l4x5",
+    stm32_mcu = "stm32l4x6",
+    stm32_mcu = "stm32l4r5",
+    stm32_mcu = "stm32l4r7",
+    stm32_mcu = "stm32l4r9",
+    stm32_mcu = "stm32l4s5",
+    stm32_mcu = "stm32l4s7",
+    stm32_mcu = "stm32l4s9"
+))]
+use crate::common::{DmaRxReq, DmaTxReq};
+use crate::common::{DrvClockSel, DrvDmaRx, DrvDmaTx, DrvRcc};
+#[cfg(feature = "gpio")]
+#[cfg(any(
+    stm32_mcu = "stm32f100",
+    stm32_mcu = "stm32f101",
+    stm32_mcu = "stm32f102",
+    stm32_mcu = "stm32f103",
+    stm32_mcu = "stm32f107"
+))]
+use crate::gpio::Input;
+#[cfg(feature = "gpio")]
+use crate::gpio::{Alternate, GpioPin, PinAf, PinMode, PushPull};
 use core::{fmt, ptr::read_volatile};
 use drone_core::inventory::{self, Inventory0, Inventory1};
 use drone_cortex_m::{
@@ -11,16 +33,57 @@ use drone_cortex_m::{
     reg::prelude::*,
     thr::prelude::*,
 };
-use drone_stm32_map::periph::{
-    dma::ch::DmaChMap,
-    uart::{traits::*, UartMap, UartPeriph},
-};
+#[cfg(feature = "gpio")]
+use drone_stm32_map::periph::gpio::pin::GpioPinMap;
+use drone_stm32_map::periph::uart::{traits::*, UartMap, UartPeriph};
 use futures::prelude::*;
 
+dma_ch! {
+    use crate::dma::DmaChEn;
+    use drone_stm32_map::periph::dma::ch::DmaChMap;
+}
+
+dma_st! {
+    use crate::dma::DmaStEn;
+    use drone_stm32_map::periph::dma::st::DmaStMap;
+}
+
 /// UART receive stream overflow.
 #[derive(Debug)]
 pub struct UartRxOverflow;
 
+/// GPIO pin, which can carry the TX signal of the UART `T`.
+#[cfg(feature = "gpio")]
+pub trait UartTxPin<T: UartMap>: GpioPinMap {
+    /// Alternate function of the signal.
+    type Af: PinAf;
+    /// Proof of the AFIO pins mapping required by the pin, `()` if none.
+    #[cfg(any(
+        stm32_mcu = "stm32f100",
+        stm32_mcu = "stm32f101",
+        stm32_mcu = "stm32f102",
+        stm32_mcu = "stm32f103",
+        stm32_mcu = "stm32f107"
+    ))]
+    type Remap;
+}
+
+/// GPIO pin, which can carry the RX signal of the UART `T`.
+#[cfg(feature = "gpio")]
+pub trait UartRxPin<T: UartMap>: GpioPinMap {
+    /// Alternate function of the signal.
+    type Af: PinAf;
+    /// Proof of the AFIO pins mapping required by the pin, `()` if none.
+    #[cfg(any(
+        stm32_mcu = "stm32f100",
+        stm32_mcu = "stm32f101",
+        stm32_mcu = "stm32f102",
+        stm32_mcu = "stm32f103",
+        stm32_mcu = "stm32f107"
+    ))]
+    type Remap;
+}
+
 /// UART driver.
 pub struct Uart<T: UartMap, I: IntToken>(Inventory0<UartEn<T, I>>);
 
@@ -171,38 +234,217 @@ impl<T: UartMap, I: IntToken> UartEn<T, I> {
     }
 }
 
+#[cfg(all(
+    feature = "gpio",
+    any(
+        stm32_mcu = "stm32f100",
+        stm32_mcu = "stm32f101",
+        stm32_mcu = "stm32f102",
+        stm32_mcu = "stm32f103",
+        stm32_mcu = "stm32f107"
+    )
+))]
+impl<T: UartMap, I: IntToken> UartEn<T, I> {
+    /// Switches `tx` pin to the alternate function of the UART, and `rx` pin
+    /// to the floating input.
+    ///
+    /// STM32F1 requires the RX pin to be configured as an input. The pull-up
+    /// can be enabled with [`set_pull`](GpioPin::set_pull) afterwards.
+    ///
+    /// `_remap` is the proof of the pins mapping returned by
+    /// `AfioEn::set_remap`, or `()` for the peripherals without remapping.
+    pub fn setup_pins<Tx, Rx>(
+        &self,
+        tx: GpioPin<Tx, impl PinMode>,
+        rx: GpioPin<Rx, impl PinMode>,
+        _remap: Tx::Remap,
+    ) -> (GpioPin<Tx, Alternate<Tx::Af, PushPull>>, GpioPin<Rx, Input>)
+    where
+        Tx: UartTxPin<T>,
+        Rx: UartRxPin<T, Remap = Tx::Remap>,
+    {
+        (tx.into_alternate(), rx.into_input())
+    }
+}
+
+#[cfg(all(
+    feature = "gpio",
+    not(any(
+        stm32_mcu = "stm32f100",
+        stm32_mcu = "stm32f101",
+        stm32_mcu = "stm32f102",
+        stm32_mcu = "stm32f103",
+        stm32_mcu = "stm32f107"
+    ))
+))]
+impl<T: UartMap, I: IntToken> UartEn<T, I> {
+    /// Switches `tx` and `rx` pins to the alternate functions of the UART.
+    pub fn setup_pins<Tx, Rx>(
+        &self,
+        tx: GpioPin<Tx, impl PinMode>,
+        rx: GpioPin<Rx, impl PinMode>,
+    ) -> (
+        GpioPin<Tx, Alternate<Tx::Af, PushPull>>,
+        GpioPin<Rx, Alternate<Rx::Af, PushPull>>,
+    )
+    where
+        Tx: UartTxPin<T>,
+        Rx: UartRxPin<T>,
+    {
+        (tx.into_alternate(), rx.into_alternate())
+    }
+}
+
 impl<T: UartMap, I: IntToken> inventory::Item for UartEn<T, I> {
     fn teardown(&mut self, _token: &mut inventory::GuardToken<Self>) {
         self.periph.rcc_busenr_uarten.clear_bit()
     }
 }
 
-impl<T: UartMap, I: IntToken, Rx: DmaChMap> DrvDmaRx<Rx> for Uart<T, I> {
-    #[inline]
-    fn dma_rx_paddr_init(&self, dma_rx: &DmaChEn<Rx, impl IntToken>) {
-        self.0.dma_rx_paddr_init(dma_rx);
+dma_ch! {
+    impl<T: UartMap, I: IntToken, Rx: DmaChMap> DrvDmaRx<Rx> for Uart<T, I> {
+        #[inline]
+        fn dma_rx_paddr_init(&self, dma_rx: &DmaChEn<Rx, impl IntToken>) {
+            self.0.dma_rx_paddr_init(dma_rx);
+        }
     }
-}
 
-impl<T: UartMap, I: IntToken, Tx: DmaChMap> DrvDmaTx<Tx> for Uart<T, I> {
-    #[inline]
-    fn dma_tx_paddr_init(&self, dma_tx: &DmaChEn<Tx, impl IntToken>) {
-        self.0.dma_tx_paddr_init(dma_tx);
+    impl<T: UartMap, I: IntToken, Tx: DmaChMap> DrvDmaTx<Tx> for Uart<T, I> {
+        #[inline]
+        fn dma_tx_paddr_init(&self, dma_tx: &DmaChEn<Tx, impl IntToken>) {
+            self.0.dma_tx_paddr_init(dma_tx);
+        }
     }
-}
 
-impl<T: UartMap, I: IntToken, Rx: DmaChMap> DrvDmaRx<Rx> for UartEn<T, I> {
-    fn dma_rx_paddr_init(&self, dma_rx: &DmaChEn<Rx, impl IntToken>) {
-        unsafe { dma_rx.set_paddr(self.periph.uart_rdr.as_ptr()) };
+    impl<T: UartMap, I: IntToken, Rx: DmaChMap> DrvDmaRx<Rx> for UartEn<T, I> {
+        fn dma_rx_paddr_init(&self, dma_rx: &DmaChEn<Rx, impl IntToken>) {
+            unsafe { dma_rx.set_paddr(self.periph.uart_rdr.as_ptr()) };
+        }
+    }
+
+    impl<T: UartMap, I: IntToken, Tx: DmaChMap> DrvDmaTx<Tx> for UartEn<T, I> {
+        fn dma_tx_paddr_init(&self, dma_tx: &DmaChEn<Tx, impl IntToken>) {
+            unsafe { dma_tx.set_paddr(self.periph.uart_tdr.as_mut_ptr()) };
+        }
     }
 }
 
-impl<T: UartMap, I: IntToken, Tx: DmaChMap> DrvDmaTx<Tx> for UartEn<T, I> {
-    fn dma_tx_paddr_init(&self, dma_tx: &DmaChEn<Tx, impl IntToken>) {
-        unsafe { dma_tx.set_paddr(self.periph.uart_tdr.as_mut_ptr()) };
+dma_st! {
+    impl<T: UartMap, I: IntToken, Rx: DmaStMap> DrvDmaRx<Rx> for Uart<T, I> {
+        #[inline]
+        fn dma_rx_paddr_init(&self, dma_rx: &DmaStEn<Rx, impl IntToken>) {
+            self.0.dma_rx_paddr_init(dma_rx);
+        }
+    }
+
+    impl<T: UartMap, I: IntToken, Tx: DmaStMap> DrvDmaTx<Tx> for Uart<T, I> {
+        #[inline]
+        fn dma_tx_paddr_init(&self, dma_tx: &DmaStEn<Tx, impl IntToken>) {
+            self.0.dma_tx_paddr_init(dma_tx);
+        }
+    }
+
+    impl<T: UartMap, I: IntToken, Rx: DmaStMap> DrvDmaRx<Rx> for UartEn<T, I> {
+        fn dma_rx_paddr_init(&self, dma_rx: &DmaStEn<Rx, impl IntToken>) {
+            unsafe { dma_rx.set_paddr(self.periph.uart_rdr.as_ptr()) };
+        }
+    }
+
+    impl<T: UartMap, I: IntToken, Tx: DmaStMap> DrvDmaTx<Tx> for UartEn<T, I> {
+        fn dma_tx_paddr_init(&self, dma_tx: &DmaStEn<Tx, impl IntToken>) {
+            unsafe { dma_tx.set_paddr(self.periph.uart_tdr.as_mut_ptr()) };
+        }
     }
 }
 
+#[cfg(any(
+    stm32_mcu = "stm32l4x1",
+    stm32_mcu = "stm32l4x2",
+    stm32_mcu = "stm32l4x3",
+    stm32_mcu = "stm32l4x5",
+    stm32_mcu = "stm32l4x6",
+    stm32_mcu = "stm32l4r5",
+    stm32_mcu = "stm32l4r7",
+    stm32_mcu = "stm32l4r9",
+    stm32_mcu = "stm32l4s5",
+    stm32_mcu = "stm32l4s7",
+    stm32_mcu = "stm32l4s9"
+))]
+impl<T, I, Rx> DmaRxReq<Rx> for Uart<T, I>
+where
+    T: UartMap + DmaRxReq<Rx>,
+    I: IntToken,
+    Rx: DmaChMap,
+{
+    const DMA_RX_REQ: u32 = T::DMA_RX_REQ;
+}
+
+#[cfg(any(
+    stm32_mcu = "stm32l4x1",
+    stm32_mcu = "stm32l4x2",
+    stm32_mcu = "stm32l4x3",
+    stm32_mcu = "stm32l4x5",
+    stm32_mcu = "stm32l4x6",
+    stm32_mcu = "stm32l4r5",
+    stm32_mcu = "stm32l4r7",
+    stm32_mcu = "stm32l4r9",
+    stm32_mcu = "stm32l4s5",
+    stm32_mcu = "stm32l4s7",
+    stm32_mcu = "stm32l4s9"
+))]
+impl<T, I, Rx> DmaRxReq<Rx> for UartEn<T, I>
+where
+    T: UartMap + DmaRxReq<Rx>,
+    I: IntToken,
+    Rx: DmaChMap,
+{
+    const DMA_RX_REQ: u32 = T::DMA_RX_REQ;
+}
+
+#[cfg(any(
+    stm32_mcu = "stm32l4x1",
+    stm32_mcu = "stm32l4x2",
+    stm32_mcu = "stm32l4x3",
+    stm32_mcu = "stm32l4x5",
+    stm32_mcu = "stm32l4x6",
+    stm32_mcu = "stm32l4r5",
+    stm32_mcu = "stm32l4r7",
+    stm32_mcu = "stm32l4r9",
+    stm32_mcu = "stm32l4s5",
+    stm32_mcu = "stm32l4s7",
+    stm32_mcu = "stm32l4s9"
+))]
+impl<T, I, Tx> DmaTxReq<Tx> for Uart<T, I>
+where
+    T: UartMap + DmaTxReq<Tx>,
+    I: IntToken,
+    Tx: DmaChMap,
+{
+    const DMA_TX_REQ: u32 = T::DMA_TX_REQ;
+}
+
+#[cfg(any(
+    stm32_mcu = "stm32l4x1",
+    stm32_mcu = "stm32l4x2",
+    stm32_mcu = "stm32l4x3",
+    stm32_mcu = "stm32l4x5",
+    stm32_mcu = "stm32l4x6",
+    stm32_mcu = "stm32l4r5",
+    stm32_mcu = "stm32l4r7",
+    stm32_mcu = "stm32l4r9",
+    stm32_mcu = "stm32l4s5",
+    stm32_mcu = "stm32l4s7",
+    stm32_mcu = "stm32l4s9"
+))]
+impl<T, I, Tx> DmaTxReq<Tx> for UartEn<T, I>
+where
+    T: UartMap + DmaTxReq<Tx>,
+    I: IntToken,
+    Tx: DmaChMap,
+{
+    const DMA_TX_REQ: u32 = T::DMA_TX_REQ;
+}
+
 #[allow(missing_docs)]
 impl<T: UartMap, I: IntToken> UartEn<T, I> {
     #[inline]
@@ -270,6 +512,480 @@ impl<T: UartMap, I: IntToken> DrvClockSel for UartEn<T, I> {
     }
 }
 
+#[cfg(all(
+    feature = "gpio",
+    any(
+        stm32_mcu = "stm32f100",
+        stm32_mcu = "stm32f101",
+        stm32_mcu = "stm32f102",
+        stm32_mcu = "stm32f103",
+        stm32_mcu = "stm32f107"
+    )
+))]
+mod pins {
+    use super::{UartRxPin, UartTxPin};
+    #[cfg(feature = "afio")]
+    use crate::afio::{
+        Usart1NotRemapped, Usart1Remapped, Usart2NotRemapped, Usart2Remapped, Usart3FullRemapped,
+        Usart3NotRemapped, Usart3PartialRemapped,
+    };
+    use crate::gpio::AfRemap;
+    #[cfg(feature = "afio")]
+    use drone_stm32_map::periph::gpio::pin::{GpioB6, GpioB7, GpioD5, GpioD6, GpioD8, GpioD9};
+    #[cfg(any(feature = "afio", not(stm32_mcu = "stm32f102")))]
+    use drone_stm32_map::periph::gpio::pin::{GpioC10, GpioC11};
+    use drone_stm32_map::periph::{
+        gpio::pin::{GpioA10, GpioA2, GpioA3, GpioA9, GpioB10, GpioB11},
+        uart::{Usart1, Usart2, Usart3},
+    };
+    #[cfg(not(stm32_mcu = "stm32f102"))]
+    use drone_stm32_map::periph::{
+        gpio::pin::{GpioC12, GpioD2},
+        uart::{Uart4, Uart5},
+    };
+
+    #[cfg(not(feature = "afio"))]
+    pin_map! {
+        UartTxPin<Usart1> {
+            GpioA9 => AfRemap,
+        }
+        UartRxPin<Usart1> {
+            GpioA10 => AfRemap,
+        }
+        UartTxPin<Usart2> {
+            GpioA2 => AfRemap,
+        }
+        UartRxPin<Usart2> {
+            GpioA3 => AfRemap,
+        }
+        UartTxPin<Usart3> {
+            GpioB10 => AfRemap,
+        }
+        UartRxPin<Usart3> {
+            GpioB11 => AfRemap,
+        }
+    }
+
+    #[cfg(feature = "afio")]
+    pin_map! {
+        UartTxPin<Usart1> {
+            GpioA9 => AfRemap if Usart1NotRemapped,
+            GpioB6 => AfRemap if Usart1Remapped,
+        }
+        UartRxPin<Usart1> {
+            GpioA10 => AfRemap if Usart1NotRemapped,
+            GpioB7 => AfRemap if Usart1Remapped,
+        }
+        UartTxPin<Usart2> {
+            GpioA2 => AfRemap if Usart2NotRemapped,
+            GpioD5 => AfRemap if Usart2Remapped,
+        }
+        UartRxPin<Usart2> {
+            GpioA3 => AfRemap if Usart2NotRemapped,
+            GpioD6 => AfRemap if Usart2Remapped,
+        }
+        UartTxPin<Usart3> {
+            GpioB10 => AfRemap if Usart3NotRemapped,
+            GpioC10 => AfRemap if Usart3PartialRemapped,
+            GpioD8 => AfRemap if Usart3FullRemapped,
+        }
+        UartRxPin<Usart3> {
+            GpioB11 => AfRemap if Usart3NotRemapped,
+            GpioC11 => AfRemap if Usart3PartialRemapped,
+            GpioD9 => AfRemap if Usart3FullRemapped,
+        }
+    }
+
+    #[cfg(not(stm32_mcu = "stm32f102"))]
+    pin_map! {
+        UartTxPin<Uart4> {
+            GpioC10 => AfRemap,
+        }
+        UartRxPin<Uart4> {
+            GpioC11 => AfRemap,
+        }
+        UartTxPin<Uart5> {
+            GpioC12 => AfRemap,
+        }
+        UartRxPin<Uart5> {
+            GpioD2 => AfRemap,
+        }
+    }
+}
+
+#[cfg(all(
+    feature = "gpio",
+    any(
+        stm32_mcu = "stm32f401",
+        stm32_mcu = "stm32f405",
+        stm32_mcu = "stm32f407",
+        stm32_mcu = "stm32f410",
+        stm32_mcu = "stm32f411",
+        stm32_mcu = "stm32f412",
+        stm32_mcu = "stm32f413",
+        stm32_mcu = "stm32f427",
+        stm32_mcu = "stm32f429",
+        stm32_mcu = "stm32f446",
+        stm32_mcu = "stm32f469"
+    )
+))]
+mod pins {
+    use super::{UartRxPin, UartTxPin};
+    use crate::gpio::Af7;
+    #[cfg(any(
+        stm32_mcu = "stm32f405",
+        stm32_mcu = "stm32f407",
+        stm32_mcu = "stm32f413",
+        stm32_mcu = "stm32f427",
+        stm32_mcu = "stm32f429",
+        stm32_mcu = "stm32f446",
+        stm32_mcu = "stm32f469"
+    ))]
+    use crate::gpio::Af8;
+    #[cfg(any(
+        stm32_mcu = "stm32f405",
+        stm32_mcu = "stm32f407",
+        stm32_mcu = "stm32f413",
+        stm32_mcu = "stm32f427",
+        stm32_mcu = "stm32f429",
+        stm32_mcu = "stm32f446",
+        stm32_mcu = "stm32f469"
+    ))]
+    use drone_stm32_map::periph::{
+        gpio::pin::{GpioA0, GpioA1, GpioC12, GpioD2},
+        uart::{Uart4, Uart5},
+    };
+    use drone_stm32_map::periph::{
+        gpio::pin::{GpioA10, GpioA2, GpioA3, GpioA9, GpioB6, GpioB7, GpioD5, GpioD6},
+        uart::{Usart1, Usart2},
+    };
+    #[cfg(not(any(
+        stm32_mcu = "stm32f401",
+        stm32_mcu = "stm32f410",
+        stm32_mcu = "stm32f411"
+    )))]
+    use drone_stm32_map::periph::{
+        gpio::pin::{GpioB10, GpioB11, GpioC10, GpioC11, GpioD8, GpioD9},
+        uart::Usart3,
+    };
+
+    pin_map! {
+        UartTxPin<Usart1> {
+            GpioA9 => Af7,
+            GpioB6 => Af7,
+        }
+        UartRxPin<Usart1> {
+            GpioA10 => Af7,
+            GpioB7 => Af7,
+        }
+        UartTxPin<Usart2> {
+            GpioA2 => Af7,
+            GpioD5 => Af7,
+        }
+        UartRxPin<Usart2> {
+            GpioA3 => Af7,
+            GpioD6 => Af7,
+        }
+    }
+
+    #[cfg(not(any(
+        stm32_mcu = "stm32f401",
+        stm32_mcu = "stm32f410",
+        stm32_mcu = "stm32f411"
+    )))]
+    pin_map! {
+        UartTxPin<Usart3> {
+            GpioB10 => Af7,
+            GpioC10 => Af7,
+            GpioD8 => Af7,
+        }
+        UartRxPin<Usart3> {
+            GpioB11 => Af7,
+            GpioC11 => Af7,
+            GpioD9 => Af7,
+        }
+    }
+
+    #[cfg(any(
+        stm32_mcu = "stm32f405",
+        stm32_mcu = "stm32f407",
+        stm32_mcu = "stm32f413",
+        stm32_mcu = "stm32f427",
+        stm32_mcu = "stm32f429",
+        stm32_mcu = "stm32f446",
+        stm32_mcu = "stm32f469"
+    ))]
+    pin_map! {
+        UartTxPin<Uart4> {
+            GpioA0 => Af8,
+            GpioC10 => Af8,
+        }
+        UartRxPin<Uart4> {
+            GpioA1 => Af8,
+            GpioC11 => Af8,
+        }
+        UartTxPin<Uart5> {
+            GpioC12 => Af8,
+        }
+        UartRxPin<Uart5> {
+            GpioD2 => Af8,
+        }
+    }
+}
+
+#[cfg(all(
+    feature = "gpio",
+    any(
+        stm32_mcu = "stm32l4x1",
+        stm32_mcu = "stm32l4x2",
+        stm32_mcu = "stm32l4x3",
+        stm32_mcu = "stm32l4x5",
+        stm32_mcu = "stm32l4x6",
+        stm32_mcu = "stm32l4r5",
+        stm32_mcu = "stm32l4r7",
+        stm32_mcu = "stm32l4r9",
+        stm32_mcu = "stm32l4s5",
+        stm32_mcu = "stm32l4s7",
+        stm32_mcu = "stm32l4s9"
+    )
+))]
+mod pins {
+    use super::{UartRxPin, UartTxPin};
+    use crate::gpio::Af7;
+    #[cfg(any(
+        stm32_mcu = "stm32l4x5",
+        stm32_mcu = "stm32l4x6",
+        stm32_mcu = "stm32l4r5",
+        stm32_mcu = "stm32l4r7",
+        stm32_mcu = "stm32l4r9",
+        stm32_mcu = "stm32l4s5",
+        stm32_mcu = "stm32l4s7",
+        stm32_mcu = "stm32l4s9"
+    ))]
+    use crate::gpio::Af8;
+    #[cfg(any(
+        stm32_mcu = "stm32l4x5",
+        stm32_mcu = "stm32l4x6",
+        stm32_mcu = "stm32l4r5",
+        stm32_mcu = "stm32l4r7",
+        stm32_mcu = "stm32l4r9",
+        stm32_mcu = "stm32l4s5",
+        stm32_mcu = "stm32l4s7",
+        stm32_mcu = "stm32l4s9"
+    ))]
+    use drone_stm32_map::periph::{
+        gpio::pin::{GpioA0, GpioA1, GpioC12, GpioD2},
+        uart::{Uart4, Uart5},
+    };
+    use drone_stm32_map::periph::{
+        gpio::pin::{
+            GpioA10, GpioA2, GpioA3, GpioA9, GpioB10, GpioB11, GpioB6, GpioB7, GpioC10, GpioC11,
+            GpioC4, GpioC5, GpioD5, GpioD6,
+        },
+        uart::{Usart1, Usart2, Usart3},
+    };
+
+    pin_map! {
+        UartTxPin<Usart1> {
+            GpioA9 => Af7,
+            GpioB6 => Af7,
+        }
+        UartRxPin<Usart1> {
+            GpioA10 => Af7,
+            GpioB7 => Af7,
+        }
+        UartTxPin<Usart2> {
+            GpioA2 => Af7,
+            GpioD5 => Af7,
+        }
+        UartRxPin<Usart2> {
+            GpioA3 => Af7,
+            GpioD6 => Af7,
+        }
+        UartTxPin<Usart3> {
+            GpioB10 => Af7,
+            GpioC4 => Af7,
+            GpioC10 => Af7,
+        }
+        UartRxPin<Usart3> {
+            GpioB11 => Af7,
+            GpioC5 => Af7,
+            GpioC11 => Af7,
+        }
+    }
+
+    #[cfg(any(
+        stm32_mcu = "stm32l4x5",
+        stm32_mcu = "stm32l4x6",
+        stm32_mcu = "stm32l4r5",
+        stm32_mcu = "stm32l4r7",
+        stm32_mcu = "stm32l4r9",
+        stm32_mcu = "stm32l4s5",
+        stm32_mcu = "stm32l4s7",
+        stm32_mcu = "stm32l4s9"
+    ))]
+    pin_map! {
+        UartTxPin<Uart4> {
+            GpioA0 => Af8,
+            GpioC10 => Af8,
+        }
+        UartRxPin<Uart4> {
+            GpioA1 => Af8,
+            GpioC11 => Af8,
+        }
+        UartTxPin<Uart5> {
+            GpioC12 => Af8,
+        }
+        UartRxPin<Uart5> {
+            GpioD2 => Af8,
+        }
+    }
+}
+
+#[cfg(any(
+    stm32_mcu = "stm32l4x1",
+    stm32_mcu = "stm32l4x2",
+    stm32_mcu = "stm32l4x3",
+    stm32_mcu = "stm32l4x5",
+    stm32_mcu = "stm32l4x6"
+))]
+mod dma_req {
+    use crate::common::{DmaRxReq, DmaTxReq};
+    use drone_stm32_map::periph::{
+        dma::ch::{Dma1Ch2, Dma1Ch3, Dma1Ch4, Dma1Ch5, Dma1Ch6, Dma1Ch7, Dma2Ch6, Dma2Ch7},
+        uart::{Lpuart1, Usart1, Usart2, Usart3},
+    };
+    #[cfg(any(stm32_mcu = "stm32l4x5", stm32_mcu = "stm32l4x6"))]
+    use drone_stm32_map::periph::{
+        dma::ch::{Dma2Ch1, Dma2Ch2, Dma2Ch3, Dma2Ch5},
+        uart::{Uart4, Uart5},
+    };
+
+    impl DmaTxReq<Dma1Ch4> for Usart1 {
+        const DMA_TX_REQ: u32 = 2;
+    }
+
+    impl DmaRxReq<Dma1Ch5> for Usart1 {
+        const DMA_RX_REQ: u32 = 2;
+    }
+
+    impl DmaTxReq<Dma2Ch6> for Usart1 {
+        const DMA_TX_REQ: u32 = 2;
+    }
+
+    impl DmaRxReq<Dma2Ch7> for Usart1 {
+        const DMA_RX_REQ: u32 = 2;
+    }
+
+    impl DmaRxReq<Dma1Ch6> for Usart2 {
+        const DMA_RX_REQ: u32 = 2;
+    }
+
+    impl DmaTxReq<Dma1Ch7> for Usart2 {
+        const DMA_TX_REQ: u32 = 2;
+    }
+
+    impl DmaTxReq<Dma1Ch2> for Usart3 {
+        const DMA_TX_REQ: u32 = 2;
+    }
+
+    impl DmaRxReq<Dma1Ch3> for Usart3 {
+        const DMA_RX_REQ: u32 = 2;
+    }
+
+    #[cfg(any(stm32_mcu = "stm32l4x5", stm32_mcu = "stm32l4x6"))]
+    impl DmaTxReq<Dma2Ch3> for Uart4 {
+        const DMA_TX_REQ: u32 = 2;
+    }
+
+    #[cfg(any(stm32_mcu = "stm32l4x5", stm32_mcu = "stm32l4x6"))]
+    impl DmaRxReq<Dma2Ch5> for Uart4 {
+        const DMA_RX_REQ: u32 = 2;
+    }
+
+    #[cfg(any(stm32_mcu = "stm32l4x5", stm32_mcu = "stm32l4x6"))]
+    impl DmaTxReq<Dma2Ch1> for Uart5 {
+        const DMA_TX_REQ: u32 = 2;
+    }
+
+    #[cfg(any(stm32_mcu = "stm32l4x5", stm32_mcu = "stm32l4x6"))]
+    impl DmaRxReq<Dma2Ch2> for Uart5 {
+        const DMA_RX_REQ: u32 = 2;
+    }
+
+    impl DmaTxReq<Dma2Ch6> for Lpuart1 {
+        const DMA_TX_REQ: u32 = 4;
+    }
+
+    impl DmaRxReq<Dma2Ch7> for Lpuart1 {
+        const DMA_RX_REQ: u32 = 4;
+    }
+}
+
+#[cfg(any(
+    stm32_mcu = "stm32l4r5",
+    stm32_mcu = "stm32l4r7",
+    stm32_mcu = "stm32l4r9",
+    stm32_mcu = "stm32l4s5",
+    stm32_mcu = "stm32l4s7",
+    stm32_mcu = "stm32l4s9"
+))]
+mod dma_req {
+    use crate::common::{DmaRxReq, DmaTxReq};
+    use drone_stm32_map::periph::{
+        dma::ch::DmaChMap,
+        uart::{Lpuart1, Uart4, Uart5, Usart1, Usart2, Usart3},
+    };
+
+    impl<Rx: DmaChMap> DmaRxReq<Rx> for Usart1 {
+        const DMA_RX_REQ: u32 = 24;
+    }
+
+    impl<Tx: DmaChMap> DmaTxReq<Tx> for Usart1 {
+        const DMA_TX_REQ: u32 = 25;
+    }
+
+    impl<Rx: DmaChMap> DmaRxReq<Rx> for Usart2 {
+        const DMA_RX_REQ: u32 = 26;
+    }
+
+    impl<Tx: DmaChMap> DmaTxReq<Tx> for Usart2 {
+        const DMA_TX_REQ: u32 = 27;
+    }
+
+    impl<Rx: DmaChMap> DmaRxReq<Rx> for Usart3 {
+        const DMA_RX_REQ: u32 = 28;
+    }
+
+    impl<Tx: DmaChMap> DmaTxReq<Tx> for Usart3 {
+        const DMA_TX_REQ: u32 = 29;
+    }
+
+    impl<Rx: DmaChMap> DmaRxReq<Rx> for Uart4 {
+        const DMA_RX_REQ: u32 = 30;
+    }
+
+    impl<Tx: DmaChMap> DmaTxReq<Tx> for Uart4 {
+        const DMA_TX_REQ: u32 = 31;
+    }
+
+    impl<Rx: DmaChMap> DmaRxReq<Rx> for Uart5 {
+        const DMA_RX_REQ: u32 = 32;
+    }
+
+    impl<Tx: DmaChMap> DmaTxReq<Tx> for Uart5 {
+        const DMA_TX_REQ: u32 = 33;
+    }
+
+    impl<Rx: DmaChMap> DmaRxReq<Rx> for Lpuart1 {
+        const DMA_RX_REQ: u32 = 34;
+    }
+
+    impl<Tx: DmaChMap> DmaTxReq<Tx> for Lpuart1 {
+        const DMA_TX_REQ: u32 = 35;
+    }
+}
+
 impl fmt::Display for UartRxOverflow {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "UART RX stream overflow.")